@@ -2,4 +2,12 @@ fn main() {
     println!("cargo:rustc-link-arg-bins=--nmagic");
     println!("cargo:rustc-link-arg-bins=-Tlink.x");
     println!("cargo:rustc-link-arg-bins=-Tdefmt.x");
+
+    // On-target tests need the same memory layout as the examples; host unit
+    // tests must not get these linker scripts.
+    if std::env::var("TARGET").is_ok_and(|target| target.starts_with("thumb")) {
+        println!("cargo:rustc-link-arg-tests=--nmagic");
+        println!("cargo:rustc-link-arg-tests=-Tlink.x");
+        println!("cargo:rustc-link-arg-tests=-Tdefmt.x");
+    }
 }
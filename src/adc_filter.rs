@@ -0,0 +1,160 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Noise reduction and defensive reads for ADC readings.
+
+#[cfg(target_arch = "arm")]
+use core::future::Future;
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::adc::{Adc, AdcChannel, Instance};
+#[cfg(target_arch = "arm")]
+use embassy_time::{with_timeout, Duration};
+
+/// Take `samples` readings of `pin` in a row and return their mean.
+///
+/// Averaging N samples reduces random noise by a factor √N, at the cost of N
+/// conversion times per reading. `samples` is clamped to at least 1.
+#[cfg(target_arch = "arm")]
+pub fn read_averaged<T: Instance>(adc: &mut Adc<'_, T>, pin: &mut impl AdcChannel<T>, samples: u16) -> u16 {
+    let samples = samples.max(1);
+    let sum: u32 = (0..samples).map(|_| u32::from(adc.blocking_read(pin))).sum();
+    // Adding half the divisor rounds to the nearest value instead of down
+    ((sum + u32::from(samples) / 2) / u32::from(samples)) as u16
+}
+
+/// Why [`read_with_timeout`] returned no value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ReadError<E> {
+    /// The read did not complete in time.
+    Timeout,
+    /// The read completed with an error of its own.
+    Failed(E),
+}
+
+/// Await `read`, giving up if it takes longer than `timeout`.
+///
+/// `read` can be any future returning a `Result`, such as a DMA ADC read or
+/// a UART read; for a future that can't fail, wrap its value in `Ok`. A
+/// timeout is logged as an error here, since it means the hardware did not
+/// respond; errors of `read` itself are passed on for the caller to handle.
+///
+/// The timeout can only fire while `read` is waiting: a blocking call such
+/// as `Adc::blocking_read` inside it runs to completion first.
+#[cfg(target_arch = "arm")]
+pub async fn read_with_timeout<T, E>(
+    timeout: Duration,
+    read: impl Future<Output = Result<T, E>>,
+) -> Result<T, ReadError<E>> {
+    match with_timeout(timeout, read).await {
+        Ok(result) => result.map_err(ReadError::Failed),
+        Err(_) => {
+            defmt::error!("Read took longer than {} us", timeout.as_micros());
+            Err(ReadError::Timeout)
+        }
+    }
+}
+
+/// Exponential moving average.
+///
+/// Each update moves the average a fraction `alpha` of the way towards the
+/// new sample. A small alpha gives a smooth but slow output, a large one
+/// follows changes quickly but lets more noise through: after a step
+/// change, the output covers about 63% of the step in `1 / alpha` updates.
+#[derive(Clone, Copy, Debug)]
+pub struct Ema {
+    alpha: f32,
+    value: Option<f32>,
+}
+
+impl Ema {
+    /// Create an empty average; `alpha` is clamped to 0.0–1.0.
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            value: None,
+        }
+    }
+
+    /// Add a sample and return the new average.
+    ///
+    /// The first sample initializes the average, so the output doesn't have
+    /// to ramp up from zero.
+    pub fn update(&mut self, sample: u16) -> u16 {
+        let sample = f32::from(sample);
+        let value = match self.value {
+            Some(value) => value + self.alpha * (sample - value),
+            None => sample,
+        };
+        self.value = Some(value);
+        (value + 0.5) as u16
+    }
+
+    /// Current average, or `None` before the first sample.
+    pub fn value(&self) -> Option<u16> {
+        self.value.map(|value| (value + 0.5) as u16)
+    }
+
+    /// Forget the history; the next sample starts a new average.
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_initializes() {
+        let mut ema = Ema::new(0.1);
+        assert_eq!(ema.value(), None);
+        assert_eq!(ema.update(2000), 2000);
+        assert_eq!(ema.value(), Some(2000));
+    }
+
+    #[test]
+    fn moves_a_fraction_towards_the_sample() {
+        let mut ema = Ema::new(0.25);
+        ema.update(1000);
+        assert_eq!(ema.update(2000), 1250);
+        assert_eq!(ema.update(2000), 1438);
+    }
+
+    #[test]
+    fn converges_to_a_constant_input() {
+        let mut ema = Ema::new(0.1);
+        ema.update(0);
+        for _ in 0..200 {
+            ema.update(4095);
+        }
+        assert_eq!(ema.value(), Some(4095));
+    }
+
+    #[test]
+    fn alpha_one_follows_the_input() {
+        let mut ema = Ema::new(1.0);
+        ema.update(100);
+        assert_eq!(ema.update(3000), 3000);
+    }
+
+    #[test]
+    fn reset_restarts_from_next_sample() {
+        let mut ema = Ema::new(0.1);
+        ema.update(100);
+        ema.reset();
+        assert_eq!(ema.update(3000), 3000);
+    }
+}
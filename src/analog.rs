@@ -0,0 +1,166 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Analog conversion helpers.
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::adc::{Adc, VrefInt};
+#[cfg(target_arch = "arm")]
+use embassy_stm32::peripherals::ADC1;
+
+/// Typical internal reference voltage, in millivolts.
+///
+/// From http://www.st.com/resource/en/datasheet/DM00071990.pdf
+/// 6.3.24 Reference voltage
+pub const VREFINT_MV: u32 = 1210;
+
+/// Convert a raw ADC sample to millivolts.
+///
+/// `vref_sample` is a reading of the internal reference voltage taken with
+/// the same ADC, which makes the result independent of the supply voltage.
+pub fn to_millivolts(sample: u16, vref_sample: u16) -> u16 {
+    (u32::from(sample) * VREFINT_MV / u32::from(vref_sample)) as u16
+}
+
+/// Supply voltage the factory calibration values were measured at, in millivolts.
+pub const VDDA_CAL_MV: u32 = 3300;
+
+/// Address of the factory VREFINT calibration sample in system memory.
+#[cfg(target_arch = "arm")]
+const VREFINT_CAL_ADDR: *const u16 = 0x1FFF_7A2A as *const u16;
+
+/// Compute the actual supply voltage VDDA, in millivolts.
+///
+/// The internal reference is stable, so its sample only changes because
+/// VDDA, the ADC full scale, does: VDDA = 3.3 V * VREFINT_CAL / sample.
+pub fn vdda_millivolts(vrefint_sample: u16, vrefint_cal: u16) -> u16 {
+    (VDDA_CAL_MV * u32::from(vrefint_cal) / u32::from(vrefint_sample)) as u16
+}
+
+/// Read the factory VREFINT calibration sample from system memory.
+#[cfg(target_arch = "arm")]
+pub fn read_vrefint_cal() -> u16 {
+    // SAFETY: fixed, always readable address in the STM32F401 system memory
+    unsafe { core::ptr::read_volatile(VREFINT_CAL_ADDR) }
+}
+
+/// Measure the supply voltage VDDA, in millivolts.
+///
+/// `vrefint` is the channel returned by `Adc::enable_vrefint`; wait for
+/// `VrefInt::start_time_us()` after enabling it before the first reading.
+#[cfg(target_arch = "arm")]
+pub fn read_vdda_mv(adc: &mut Adc<'_, ADC1>, vrefint: &mut VrefInt) -> u16 {
+    vdda_millivolts(adc.blocking_read(vrefint), read_vrefint_cal())
+}
+
+/// Factory calibration of the internal temperature sensor.
+///
+/// From the STM32F401xE datasheet, 6.3.22 Temperature sensor characteristics
+/// and 6.3.23 Reference voltage. The values are raw ADC samples measured with
+/// VDDA = 3.3 V and stored in system memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TemperatureCalibration {
+    /// Temperature sensor sample at 30 °C.
+    pub ts_cal1: u16,
+    /// Temperature sensor sample at 110 °C.
+    pub ts_cal2: u16,
+    /// Internal reference voltage sample.
+    pub vrefint_cal: u16,
+}
+
+/// Temperature of the `ts_cal1` calibration point, in °C.
+pub const TS_CAL1_TEMP: f32 = 30.0;
+/// Temperature of the `ts_cal2` calibration point, in °C.
+pub const TS_CAL2_TEMP: f32 = 110.0;
+
+impl TemperatureCalibration {
+    /// Read the calibration values from the MCU system memory.
+    #[cfg(target_arch = "arm")]
+    pub fn read() -> Self {
+        // SAFETY: fixed, always readable addresses in the STM32F401 system memory
+        unsafe {
+            Self {
+                ts_cal1: core::ptr::read_volatile(0x1FFF_7A2C as *const u16),
+                ts_cal2: core::ptr::read_volatile(0x1FFF_7A2E as *const u16),
+                vrefint_cal: read_vrefint_cal(),
+            }
+        }
+    }
+}
+
+/// Convert a temperature sensor sample to degrees Celsius.
+///
+/// `vrefint_sample` rescales the sample to the 3.3 V supply the calibration
+/// values were taken at, so the result does not depend on the actual VDDA.
+pub fn to_celsius(sample: u16, vrefint_sample: u16, cal: &TemperatureCalibration) -> f32 {
+    // Sample as it would read with VDDA = 3.3 V
+    let sample = f32::from(sample) * f32::from(cal.vrefint_cal) / f32::from(vrefint_sample);
+
+    // Linear interpolation between the two calibration points
+    let (cal1, cal2) = (f32::from(cal.ts_cal1), f32::from(cal.ts_cal2));
+    (sample - cal1) * (TS_CAL2_TEMP - TS_CAL1_TEMP) / (cal2 - cal1) + TS_CAL1_TEMP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAL: TemperatureCalibration = TemperatureCalibration {
+        ts_cal1: 940,
+        ts_cal2: 1200,
+        vrefint_cal: 1500,
+    };
+
+    #[test]
+    fn vrefint_sample_gives_reference_voltage() {
+        assert_eq!(to_millivolts(1500, 1500), VREFINT_MV as u16);
+    }
+
+    #[test]
+    fn millivolts_scale_linearly() {
+        assert_eq!(to_millivolts(0, 1500), 0);
+        assert_eq!(to_millivolts(3000, 1500), 2 * VREFINT_MV as u16);
+        // Full scale with VDDA = 3.3 V: 4095 * 1210 / 1501 = 3301 mV
+        assert_eq!(to_millivolts(4095, 1501), 3301);
+    }
+
+    #[test]
+    fn calibration_sample_gives_calibration_supply() {
+        assert_eq!(vdda_millivolts(1500, 1500), VDDA_CAL_MV as u16);
+    }
+
+    #[test]
+    fn vdda_is_inversely_proportional_to_vrefint_sample() {
+        // A lower supply shrinks the ADC full scale, so the reference reads higher
+        assert_eq!(vdda_millivolts(1650, 1500), 3000);
+        assert_eq!(vdda_millivolts(1500, 1650), 3630);
+    }
+
+    #[test]
+    fn calibration_points_give_calibration_temperatures() {
+        assert!((to_celsius(940, 1500, &CAL) - 30.0).abs() < 0.01);
+        assert!((to_celsius(1200, 1500, &CAL) - 110.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn temperature_is_independent_of_supply() {
+        // At a lower VDDA both the sensor and the reference read higher
+        // by the same factor
+        let t = to_celsius(1070, 1500, &CAL);
+        let t_low_vdda = to_celsius(1177, 1650, &CAL);
+        assert!((t - 70.0).abs() < 0.01);
+        assert!((t_low_vdda - t).abs() < 0.1);
+    }
+}
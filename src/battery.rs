@@ -0,0 +1,156 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Battery voltage measurement through a resistor divider.
+//!
+//! A battery is usually above the 3.3 V the ADC can measure, so it is
+//! connected through two resistors that scale it down. The functions here
+//! undo that scaling and decide when the battery is low.
+
+use crate::hysteresis::Hysteresis;
+
+/// Full scale of the 12-bit ADC.
+pub const ADC_MAX: u32 = 4095;
+
+/// Convert a raw ADC sample to millivolts at the pin.
+///
+/// `vdda_mv` is the measured supply voltage, which is the ADC full scale
+/// (see `analog::read_vdda_mv`).
+pub fn sample_to_millivolts(sample: u16, vdda_mv: u16) -> u32 {
+    u32::from(sample) * u32::from(vdda_mv) / ADC_MAX
+}
+
+/// Resistor divider between the battery and the ADC pin.
+///
+/// `r_top` goes from the battery to the pin, `r_bottom` from the pin to
+/// ground. Only the ratio matters, so any unit can be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Divider {
+    r_top: u32,
+    r_bottom: u32,
+}
+
+impl Divider {
+    /// Create a divider from its two resistors.
+    ///
+    /// Panics if `r_bottom` is zero: the pin would be tied to ground and
+    /// measure nothing. In a `const` the mistake is a compile error.
+    pub const fn new(r_top: u32, r_bottom: u32) -> Self {
+        assert!(r_bottom > 0, "r_bottom must not be zero");
+        Self { r_top, r_bottom }
+    }
+
+    /// Battery voltage for a voltage measured at the pin, both in millivolts.
+    pub fn battery_millivolts(&self, pin_mv: u32) -> u32 {
+        (u64::from(pin_mv) * (u64::from(self.r_top) + u64::from(self.r_bottom)) / u64::from(self.r_bottom)) as u32
+    }
+}
+
+/// Low-battery detection with hysteresis, in millivolts.
+///
+/// The alarm goes on when the voltage falls below `alarm_below_mv` and only
+/// clears when it rises above `clear_above_mv`. Under load a battery near
+/// the threshold sags and recovers, so a single threshold would flicker.
+///
+/// This is a [`Hysteresis`] switch on the negated voltage: it turns on
+/// above its high threshold, while the alarm must go on below the low one.
+#[derive(Clone, Copy, Debug)]
+pub struct LowBatteryAlarm {
+    switch: Hysteresis,
+}
+
+impl LowBatteryAlarm {
+    /// Create an alarm that starts cleared.
+    ///
+    /// The thresholds are swapped if `alarm_below_mv` is greater than
+    /// `clear_above_mv`.
+    pub fn new(alarm_below_mv: u32, clear_above_mv: u32) -> Self {
+        Self {
+            switch: Hysteresis::new(-(clear_above_mv as f32), -(alarm_below_mv as f32)),
+        }
+    }
+
+    /// Feed a new battery voltage and return whether the battery is low.
+    pub fn update(&mut self, battery_mv: u32) -> bool {
+        self.switch.update(-(battery_mv as f32))
+    }
+
+    /// Current state of the alarm.
+    pub fn is_low(&self) -> bool {
+        self.switch.is_on()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_scale_with_vdda() {
+        assert_eq!(sample_to_millivolts(0, 3300), 0);
+        assert_eq!(sample_to_millivolts(4095, 3300), 3300);
+        assert_eq!(sample_to_millivolts(4095, 3000), 3000);
+        assert_eq!(sample_to_millivolts(2048, 3300), 1650);
+    }
+
+    #[test]
+    fn equal_resistors_halve_the_voltage() {
+        let divider = Divider::new(10_000, 10_000);
+        assert_eq!(divider.battery_millivolts(1_850), 3_700);
+        // Highest measurable battery voltage with VDDA = 3.3 V
+        assert_eq!(divider.battery_millivolts(3_300), 6_600);
+    }
+
+    #[test]
+    fn divider_ratio_is_applied() {
+        // 9 V battery through 47k / 10k: the pin sees 1/5.7 of it
+        let divider = Divider::new(47_000, 10_000);
+        assert_eq!(divider.battery_millivolts(1_579), 9_000);
+        assert_eq!(Divider::new(0, 10_000).battery_millivolts(1_234), 1_234);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_bottom_resistor_is_rejected() {
+        Divider::new(10_000, 0);
+    }
+
+    #[test]
+    fn alarm_goes_on_below_and_clears_above() {
+        let mut alarm = LowBatteryAlarm::new(3_500, 3_700);
+        assert!(!alarm.is_low());
+        assert!(!alarm.update(3_500));
+        assert!(alarm.update(3_499));
+        assert!(alarm.update(3_700));
+        assert!(!alarm.update(3_701));
+    }
+
+    #[test]
+    fn sag_under_load_does_not_clear_the_alarm() {
+        let mut alarm = LowBatteryAlarm::new(3_500, 3_700);
+        alarm.update(3_450);
+        for battery_mv in [3_520, 3_480, 3_650, 3_550] {
+            assert!(alarm.update(battery_mv));
+        }
+    }
+
+    #[test]
+    fn swapped_thresholds() {
+        let mut alarm = LowBatteryAlarm::new(3_700, 3_500);
+        assert!(alarm.update(3_400));
+        assert!(alarm.update(3_600));
+        assert!(!alarm.update(3_800));
+    }
+}
@@ -25,15 +25,20 @@
 use embassy_executor::Spawner;
 use embassy_stm32::gpio::{Level, Output, Speed};
 use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
 use {defmt_rtt as _, panic_probe as _};
 
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
+    init_logging();
+
 
 
     let p = embassy_stm32::init(Default::default());
-    let mut led = Output::new(p.PA5, Level::High, Speed::Low);
+    let board = take_board!(p);
+    let mut led = Output::new(board.user_led, Level::High, Speed::Low);
     loop {
         defmt::info!("Blink");
         led.set_high();
@@ -26,16 +26,22 @@
 use cortex_m_rt::entry;
 use defmt::*;
 use embassy_stm32::gpio::{Input, Level, Output, Pull, Speed};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
 use {defmt_rtt as _, panic_probe as _};
 
 #[entry]
 fn main() -> ! {
+    init_logging();
+
     info!("Hello World!");
 
     let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
 
-    let button = Input::new(p.PC13, Pull::Down);
-    let mut led1 = Output::new(p.PA5, Level::High, Speed::Low);
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = Input::new(board.user_button, Pull::None);
+    let mut led1 = Output::new(board.user_led, Level::High, Speed::Low);
 
     loop {
         if button.is_high() {
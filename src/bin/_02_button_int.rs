@@ -27,16 +27,22 @@ use defmt::*;
 use embassy_executor::Spawner;
 use embassy_stm32::exti::ExtiInput;
 use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
+    init_logging();
+
     info!("Hello World!");
 
     let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
 
-    let mut button = ExtiInput::new(p.PC13, p.EXTI13, Pull::Down);
-    let mut led1 = Output::new(p.PA5, Level::High, Speed::Low);
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let mut button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut led1 = Output::new(board.user_led, Level::High, Speed::Low);
 
     loop {
         button.wait_for_rising_edge().await;
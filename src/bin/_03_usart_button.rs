@@ -33,6 +33,9 @@ use embassy_stm32::exti::ExtiInput;
 use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Pull, Speed};
 use embassy_stm32::usart::{Config, Uart,};
 use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
 use {defmt_rtt as _, panic_probe as _};
 
 static BLINK_MS: AtomicU32 = AtomicU32::new(0);
@@ -56,15 +59,19 @@ bind_interrupts!(struct Irqs {
 });
 #[embassy_executor::main]
 async fn main(spawner: Spawner) {
+    init_logging();
+
     // Initialize and create handle for devicer peripherals
     let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
 
     // Configure the button pin (if needed) and obtain handler.
     // On the Nucleo FR401 there is a button connected to pin PC13.
-    let mut button = ExtiInput::new(p.PC13, p.EXTI13, Pull::Down);
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let mut button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
 
     //Configure UART
-    let mut usart: Uart<'_, embassy_stm32::mode::Blocking> = Uart::new_blocking(p.USART2, p.PA3, p.PA2, Config::default()).unwrap();
+    let mut usart: Uart<'_, embassy_stm32::mode::Blocking> = uart_or_halt(Uart::new_blocking(board.vcp_usart, board.vcp_rx, board.vcp_tx, Config::default()));
     //let mut usart = UartTx::new(p.USART2, p.PA2, NoDma, Config::default()).unwrap();
 
     // Create and initialize a delay variable to manage delay loop
@@ -74,7 +81,7 @@ async fn main(spawner: Spawner) {
     BLINK_MS.store(del_var, Ordering::Relaxed);
 
     // Spawn LED blinking task
-    spawner.spawn(led_task(p.PA5.degrade())).unwrap();
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
 
     // Variable to keep track of how many button presses occured
     let mut value: u8 = 0;
@@ -99,7 +106,9 @@ async fn main(spawner: Spawner) {
         core::writeln!(&mut msg, "{:02}", value).unwrap();
 
         // Transmit Message
-        let _ = usart.blocking_write(msg.as_bytes()); //usart.blocking_write(msg.as_bytes());
+        if let Err(e) = usart.blocking_write(msg.as_bytes()) {
+            log_uart_error(e);
+        }
 
         // Update Value Parameter
         value = value.wrapping_add(1);
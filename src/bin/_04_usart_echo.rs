@@ -27,6 +27,9 @@ use cortex_m_rt::entry;
 use defmt::*;
 use embassy_stm32::usart::{Config, Uart};
 use embassy_stm32::{bind_interrupts, peripherals, usart};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{drain_rx, log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
 use {defmt_rtt as _, panic_probe as _};
 
 bind_interrupts!(struct Irqs {
@@ -35,19 +38,31 @@ bind_interrupts!(struct Irqs {
 
 #[entry]
 fn main() -> ! {
+    init_logging();
+
     info!("Hello World!");
 
     let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
 
     let config = Config::default();
-    let mut usart: Uart<'_, embassy_stm32::mode::Blocking> = Uart::new_blocking(p.USART2, p.PA3, p.PA2, config).unwrap();
+    let mut usart: Uart<'_, embassy_stm32::mode::Blocking> = uart_or_halt(Uart::new_blocking(board.vcp_usart, board.vcp_rx, board.vcp_tx, config));
 
-    unwrap!(usart.blocking_write(b"Hello Embassy World!\r\n"));
+    if let Err(e) = usart.blocking_write(b"Hello Embassy World!\r\n") {
+        log_uart_error(e);
+    }
     info!("wrote Hello, starting echo");
 
     let mut buf = [0u8; 1];
     loop {
-        unwrap!(usart.blocking_read(&mut buf));
-        unwrap!(usart.blocking_write(&buf));
+        // On a receive error the corrupted byte is flushed and the echo carries on
+        if let Err(e) = usart.blocking_read(&mut buf) {
+            log_uart_error(e);
+            drain_rx(&mut usart);
+            continue;
+        }
+        if let Err(e) = usart.blocking_write(&buf) {
+            log_uart_error(e);
+        }
     }
 }
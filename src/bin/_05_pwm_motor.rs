@@ -29,10 +29,13 @@ use embassy_stm32::gpio::OutputType;
 use embassy_stm32::time::hz;
 use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
 use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
+    init_logging();
+
     let p = embassy_stm32::init(Default::default());
     info!("Hello World!");
 
@@ -29,38 +29,30 @@ use embassy_stm32::gpio::OutputType;
 use embassy_stm32::time::hz;
 use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
 use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::servo::{Servo, DEFAULT_MAX_US, DEFAULT_MIN_US};
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
+    init_logging();
+
     let p = embassy_stm32::init(Default::default());
     info!("Hello World!");
 
     let ch2_pin = PwmPin::new_ch2(p.PA9, OutputType::PushPull);
     let mut pwm = SimplePwm::new(p.TIM1, None, Some(ch2_pin), None, None, hz(50), Default::default());
-    let mut ch2 = pwm.ch2();
-    ch2.enable();
-
     info!("PWM initialized");
-    info!("PWM max duty {}", ch2.max_duty_cycle());
+    info!("PWM max duty {}", pwm.max_duty_cycle());
 
-    loop {
+    // 1.0 ms pulse at 0 degrees, 2.0 ms pulse at 180 degrees
+    let mut servo = Servo::new(pwm.ch2(), DEFAULT_MIN_US, DEFAULT_MAX_US);
 
-        ch2.set_duty_cycle_fully_off();
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,20);
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,16);
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,13);
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,12);
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,10);
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,8);
-        Timer::after_millis(500).await;
-        ch2.set_duty_cycle_fraction(1,7);
-        Timer::after_millis(500).await;
+    loop {
+        // Sweep 0 -> 180 degrees in 1 degree steps, then back
+        for angle in (0..=180).chain((0..180).rev()) {
+            servo.set_angle(angle as f32);
+            Timer::after_millis(10).await;
+        }
     }
 }
@@ -22,41 +22,37 @@
 #![no_std]
 #![no_main]
 
-use cortex_m::prelude::_embedded_hal_blocking_delay_DelayUs;
 use defmt::*;
 use embassy_executor::Spawner;
 use embassy_stm32::adc::{Adc, Temperature, VrefInt};
-use embassy_time::{Delay, Timer};
+use getting_started_embassy_stm32f401re::analog::to_millivolts;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::timing::{delay_us, sleep_ms};
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
+    init_logging();
+
     let p = embassy_stm32::init(Default::default());
 
-    let mut delay = Delay;
     let mut adc = Adc::new(p.ADC1);
     let mut pin = p.PA0;
 
     let mut vrefint = adc.enable_vrefint();
 
-    // Startup delay can be combined to the maximum of either
-    delay.delay_us(Temperature::start_time_us().max(VrefInt::start_time_us()));
+    // Startup delay can be combined to the maximum of either. A few
+    // microseconds are too short to be worth a task switch: block for them
+    delay_us(Temperature::start_time_us().max(VrefInt::start_time_us()));
 
     let vrefint_sample = adc.blocking_read(&mut vrefint);
 
-    let convert_to_millivolts = |sample| {
-        // From http://www.st.com/resource/en/datasheet/DM00071990.pdf
-        // 6.3.24 Reference voltage
-        const VREFINT_MV: u32 = 1210; // mV
-
-        (u32::from(sample) * VREFINT_MV / u32::from(vrefint_sample)) as u16
-    };
-
     loop {
         // Read pin
         let v = adc.blocking_read(&mut pin);
-        info!("PA0: {} ({} mV)", v, convert_to_millivolts(v));
+        info!("PA0: {} ({} mV)", v, to_millivolts(v, vrefint_sample));
 
-        Timer::after_millis(100).await;
+        // 100 ms is long: sleep, so other tasks could run in the meantime
+        sleep_ms(100).await;
     }
 }
@@ -24,35 +24,54 @@
 #![no_std]
 #![no_main]
 
+use defmt::*;
 use embassy_executor::Spawner;
 use embassy_stm32::{bind_interrupts,usart,peripherals};
 use embassy_stm32::usart::{Config, Uart};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
 use {defmt_rtt as _, panic_probe as _};
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
+    init_logging();
+
     let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
 
     bind_interrupts!(struct Irq {
         USART2 => usart::InterruptHandler<peripherals::USART2>;
     });
-    let mut usart = Uart::new(
-        p.USART2,
-        p.PA3,
-        p.PA2,
+    let mut usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
         Irq,
-        p.DMA1_CH6, // TX DMA channel 6 for USART2
-        p.DMA1_CH5, // %X DMA channel 5 for USART2
+        board.vcp_tx_dma, // TX DMA channel 6 for USART2
+        board.vcp_rx_dma, // %X DMA channel 5 for USART2
         Config::default(),
-    ).unwrap();
+    ));
 
-    usart.write(b"Starting Echo\r\n").await.unwrap();
+    if let Err(e) = usart.write(b"Starting Echo\r\n").await {
+        log_uart_error(e);
+    }
 
-    let mut msg: [u8; 8] = [0; 8];
+    let mut msg: [u8; 64] = [0; 64];
 
     loop {
-        // await means in this case that will read until the buffer is full
-        usart.read(&mut msg).await.unwrap();
-        usart.write(&msg).await.unwrap();
+        // Completes as soon as the line goes idle after some data, or when the buffer is full
+        match usart.read_until_idle(&mut msg).await {
+            Ok(len) => {
+                debug!("Received {} bytes", len);
+                if let Err(e) = usart.write(&msg[..len]).await {
+                    log_uart_error(e);
+                }
+            }
+            // On a receive error the partial data is discarded. No flush is needed:
+            // the next read clears the error flags and the stale byte before it
+            // restarts the DMA transfer.
+            Err(e) => log_uart_error(e),
+        }
     }
 }
@@ -0,0 +1,60 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 10: I2C Scanner                      *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::time::khz;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Scanning I2C1...");
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14)
+    let mut i2c = I2c::new_blocking(p.I2C1, p.PB8, p.PB9, khz(100), Default::default());
+
+    let mut found = 0;
+    let mut buf = [0u8; 1];
+
+    // 0x00..0x07 and 0x78..0x7F are reserved addresses
+    for addr in 0x08..=0x77u8 {
+        // A device that acknowledges its address answers the 1-byte read
+        match i2c.blocking_read(addr, &mut buf) {
+            Ok(()) => {
+                info!("Found device at {=u8:#04x}", addr);
+                found += 1;
+            }
+            // Nobody at this address, keep scanning
+            Err(i2c::Error::Nack) => {}
+            Err(e) => warn!("Error at {=u8:#04x}: {}", addr, e),
+        }
+    }
+
+    info!("Scan complete: {} device(s) found", found);
+}
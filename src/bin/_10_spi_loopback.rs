@@ -0,0 +1,64 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 11: SPI Loopback                     *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::spi::{self, Spi, MODE_0};
+use embassy_stm32::time::mhz;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Loopback wiring: connect PA7 (MOSI, D11) to PA6 (MISO, D12) with a jumper wire,
+    // so every bit shifted out is shifted back in on the same clock edge.
+    // PA5 (SCK, D13) also drives the user LED, which will flicker during the transfer.
+    let mut config = spi::Config::default();
+    config.frequency = mhz(1);
+    config.mode = MODE_0;
+
+    let mut spi = Spi::new_blocking(p.SPI1, p.PA5, p.PA7, p.PA6, config);
+
+    // Known pattern: 0x00, 0x01, ..., 0xFF
+    let mut tx = [0u8; 256];
+    for (i, b) in tx.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    let mut rx = [0u8; 256];
+
+    unwrap!(spi.blocking_transfer(&mut rx, &tx));
+
+    match tx.iter().zip(rx.iter()).position(|(t, r)| t != r) {
+        None => info!("SPI loopback PASS: {} bytes echoed", tx.len()),
+        Some(i) => error!(
+            "SPI loopback FAIL at byte {}: sent {=u8:#04x}, received {=u8:#04x}",
+            i, tx[i], rx[i]
+        ),
+    }
+}
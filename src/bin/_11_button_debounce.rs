@@ -0,0 +1,59 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 12: Button Debounce                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    info!("Hello World!");
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    let mut led1 = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    let mut presses: u32 = 0;
+
+    loop {
+        // Contact bounce shorter than the debounce window is ignored,
+        // so each physical press toggles the LED exactly once
+        button.wait_for_press().await;
+        led1.toggle();
+
+        presses = presses.wrapping_add(1);
+        info!("Pressed {} times", presses);
+    }
+}
@@ -0,0 +1,81 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 13: USART Line Echo                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, read_line, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+
+    let usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+
+    // Split into independent transmitter and receiver halves
+    let (mut tx, mut rx) = usart.split();
+
+    if let Err(e) = tx.write(b"Type a line and press Enter\r\n").await {
+        log_uart_error(e);
+    }
+
+    let mut line: String<64> = String::new();
+
+    loop {
+        // Wait for a complete line, whatever its length
+        // On a receive error the partial line is discarded
+        if let Err(e) = read_line(&mut rx, &mut line).await {
+            log_uart_error(e);
+            continue;
+        }
+        info!("Received: {}", line.as_str());
+
+        for part in [b"> ".as_slice(), line.as_bytes(), b"\r\n".as_slice()] {
+            if let Err(e) = tx.write(part).await {
+                log_uart_error(e);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 14: ADC Temperature Sensor           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime, Temperature, VrefInt};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::analog::{to_celsius, TemperatureCalibration};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::timing::delay_us;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+
+    // Factory calibration points of the sensor, stored in system memory
+    let calibration = TemperatureCalibration::read();
+
+    let mut adc = Adc::new(p.ADC1);
+
+    // The temperature sensor needs a sampling time of at least 10 µs,
+    // the longest sample time is 480 ADC clock cycles (60 µs at 8 MHz)
+    adc.set_sample_time(SampleTime::CYCLES480);
+
+    let mut vrefint = adc.enable_vrefint();
+    let mut temp = adc.enable_temperature();
+
+    // Startup delay can be combined to the maximum of either
+    delay_us(Temperature::start_time_us().max(VrefInt::start_time_us()));
+
+    loop {
+        let vrefint_sample = adc.blocking_read(&mut vrefint);
+        let t = adc.blocking_read(&mut temp);
+
+        info!("Temperature: {} ({} C)", t, to_celsius(t, vrefint_sample, &calibration));
+
+        Timer::after_secs(1).await;
+    }
+}
@@ -0,0 +1,88 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 15: ADC with DMA                     *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime, Sequence};
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Number of samples captured by each `read`. The DMA ring buffer is twice as
+// large: the DMA fills one half while the application processes the other.
+const WINDOW: usize = 256;
+const DMA_BUF_LEN: usize = WINDOW * 2;
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    // Keeps blinking while the ADC task waits for the DMA,
+    // showing that the executor is never blocked
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+
+    loop {
+        led.toggle();
+        Timer::after_millis(250).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let mut p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+
+    // ADC1 requests are served by DMA2 stream 0 (or stream 4)
+    let mut adc_dma_buf = [0u16; DMA_BUF_LEN];
+    let mut adc = Adc::new(p.ADC1).into_ring_buffered(p.DMA2_CH0, &mut adc_dma_buf);
+
+    // A single-entry sequence: every conversion samples PA0
+    adc.set_sample_sequence(Sequence::One, &mut p.PA0, SampleTime::CYCLES112);
+
+    let mut samples = [0u16; WINDOW];
+
+    loop {
+        // Suspends until the DMA has filled half of the ring buffer
+        match adc.read(&mut samples).await {
+            Ok(_) => {
+                let sum: u32 = samples.iter().map(|&s| u32::from(s)).sum();
+                let average = sum / WINDOW as u32;
+                info!("PA0 average over {} samples: {}", WINDOW, average);
+
+                // Stop converting until the next window is needed,
+                // otherwise the ADC free-runs and the ring buffer overruns
+                adc.teardown_adc();
+            }
+            Err(_) => {
+                // DMA overrun: the next `read` restarts the conversions
+                warn!("ADC DMA overrun");
+            }
+        }
+
+        Timer::after_millis(500).await;
+    }
+}
@@ -0,0 +1,68 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 16: ADC Multichannel                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, VrefInt};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::analog::to_millivolts;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::timing::delay_us;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+
+    let mut adc = Adc::new(p.ADC1);
+
+    // Arduino analog header: A0 = PA0, A1 = PA1, A2 = PA4
+    let mut a0 = p.PA0;
+    let mut a1 = p.PA1;
+    let mut a2 = p.PA4;
+
+    let mut vrefint = adc.enable_vrefint();
+    delay_us(VrefInt::start_time_us());
+
+    loop {
+        // Refresh the reference on every scan, so supply drift is compensated
+        let vrefint_sample = adc.blocking_read(&mut vrefint);
+
+        // Each read selects its own channel before converting,
+        // so one ADC can be shared by all the inputs
+        let v0 = adc.blocking_read(&mut a0);
+        let v1 = adc.blocking_read(&mut a1);
+        let v2 = adc.blocking_read(&mut a2);
+
+        info!(
+            "A0: {} mV, A1: {} mV, A2: {} mV",
+            to_millivolts(v0, vrefint_sample),
+            to_millivolts(v1, vrefint_sample),
+            to_millivolts(v2, vrefint_sample)
+        );
+
+        Timer::after_millis(200).await;
+    }
+}
@@ -0,0 +1,82 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 17: DC Motor Control                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::motor::HBridgeMotor;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    // Same wiring as _05_pwm_motor: IN1 on PB6 (TIM4_CH1, D10), IN2 on PA7 (TIM3_CH2, D11)
+    let in1_pin = PwmPin::new_ch1(p.PB6, OutputType::PushPull);
+    let in2_pin = PwmPin::new_ch2(p.PA7, OutputType::PushPull);
+
+    let mut pwm_in1 = SimplePwm::new(p.TIM4, Some(in1_pin), None, None, None, hz(100), Default::default());
+    let mut pwm_in2 = SimplePwm::new(p.TIM3, None, Some(in2_pin), None, None, hz(100), Default::default());
+
+    let mut motor = HBridgeMotor::new(pwm_in1.ch1(), pwm_in2.ch2());
+
+    loop {
+        info!("Press the button to ramp forward");
+        button.wait_for_press().await;
+        for speed in (0..=100).step_by(5) {
+            motor.set_speed(speed);
+            Timer::after_millis(100).await;
+        }
+
+        info!("Press the button to brake");
+        button.wait_for_press().await;
+        motor.brake();
+
+        info!("Press the button to ramp in reverse");
+        button.wait_for_press().await;
+        for speed in (0..=100).step_by(5) {
+            motor.set_speed(-speed);
+            Timer::after_millis(100).await;
+        }
+
+        info!("Press the button to coast");
+        button.wait_for_press().await;
+        motor.coast();
+    }
+}
@@ -0,0 +1,90 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 18: Independent Watchdog             *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_stm32::peripherals::IWDG;
+use embassy_stm32::wdg::IndependentWatchdog;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::reset::{self, ResetCause};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Watchdog timeout: the board resets if it is not petted for this long
+const WATCHDOG_TIMEOUT_US: u32 = 2_000_000;
+
+static PETTING: AtomicBool = AtomicBool::new(true);
+
+#[embassy_executor::task]
+async fn watchdog_task(mut wdg: IndependentWatchdog<'static, IWDG>) {
+    // Once unleashed the IWDG can't be stopped, only reset by the hardware
+    wdg.unleash();
+
+    loop {
+        if PETTING.load(Ordering::Relaxed) {
+            wdg.pet();
+        }
+        // Pet well within the timeout: the LSI clock of the IWDG is not accurate
+        Timer::after_millis(500).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+
+    // The RCC keeps the cause of the last reset until the flags are cleared
+    match reset::read_and_clear() {
+        ResetCause::IndependentWatchdog => warn!("Reset by the independent watchdog"),
+        cause => info!("Normal startup ({})", cause),
+    }
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let mut button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut led = Output::new(board.user_led, Level::High, Speed::Low);
+
+    let wdg = IndependentWatchdog::new(p.IWDG, WATCHDOG_TIMEOUT_US);
+    spawner.spawn(watchdog_task(wdg)).unwrap();
+
+    info!("Watchdog running, press the button to simulate a fault");
+
+    button.wait_for_falling_edge().await;
+
+    // Simulated fault: the firmware stops petting and the IWDG resets the board
+    PETTING.store(false, Ordering::Relaxed);
+    warn!("Stopped petting the watchdog, reset in about 2 s");
+
+    loop {
+        led.toggle();
+        Timer::after_millis(100).await;
+    }
+}
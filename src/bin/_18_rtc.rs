@@ -0,0 +1,81 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 19: Real-Time Clock                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::rcc::LsConfig;
+use embassy_stm32::rtc::{DateTime, DayOfWeek, Rtc, RtcConfig};
+use embassy_stm32::Config;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+// Marker stored in a backup register once the calendar has been set
+const RTC_SET_MAGIC: u32 = 0x32F4_01CE;
+const RTC_SET_REGISTER: usize = 0;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let mut config = Config::default();
+    // The NucleoF401RE has a 32.768 kHz crystal (X2) for the LSE, far more
+    // accurate than the internal LSI. Use LsConfig::default_lsi() on boards
+    // without the crystal.
+    config.rcc.ls = LsConfig::default_lse();
+    let p = embassy_stm32::init(config);
+
+    let mut rtc = Rtc::new(p.RTC, RtcConfig::default());
+
+    // The backup domain survives a reset (and a power loss if VBAT is supplied),
+    // so only set the time when the calendar has never been initialized
+    if rtc.read_backup_register(RTC_SET_REGISTER) == Some(RTC_SET_MAGIC) {
+        info!("RTC already running, keeping the current time");
+    } else {
+        let initial = DateTime::from(2024, 1, 1, DayOfWeek::Monday, 12, 0, 0).unwrap();
+        match rtc.set_datetime(initial) {
+            Ok(()) => {
+                rtc.write_backup_register(RTC_SET_REGISTER, RTC_SET_MAGIC);
+                info!("RTC initialized");
+            }
+            Err(_) => error!("Failed to set the RTC time"),
+        }
+    }
+
+    loop {
+        match rtc.now() {
+            Ok(now) => info!(
+                "{=u16}-{=u8:02}-{=u8:02} {=u8:02}:{=u8:02}:{=u8:02}",
+                now.year(),
+                now.month(),
+                now.day(),
+                now.hour(),
+                now.minute(),
+                now.second()
+            ),
+            Err(_) => error!("Failed to read the RTC"),
+        }
+        Timer::after_secs(1).await;
+    }
+}
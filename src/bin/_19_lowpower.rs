@@ -0,0 +1,82 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 20: Low-Power Blink                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+// Build with: cargo run --release --features low-power --bin _19_lowpower
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_stm32::low_power::Executor;
+use embassy_stm32::rtc::{Rtc, RtcConfig};
+use embassy_stm32::Config;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+// There is no `#[embassy_executor::main]` for the low-power executor:
+// the entry point creates it and spawns the application as a task.
+#[cortex_m_rt::entry]
+fn main() -> ! {
+    init_logging();
+
+    Executor::take().run(|spawner| {
+        unwrap!(spawner.spawn(async_main(spawner)));
+    });
+}
+
+#[embassy_executor::task]
+async fn async_main(_spawner: Spawner) {
+    let mut config = Config::default();
+    // Keeping the debug interface alive in STOP raises the current draw
+    // considerably. Set to true while debugging, as RTT logs and the probe
+    // connection are lost while the core is stopped.
+    config.enable_debug_during_sleep = false;
+    let p = embassy_stm32::init(config);
+    let board = take_board!(p);
+
+    // The RTC wakeup timer keeps time and wakes the core while it is stopped
+    let rtc = Rtc::new(p.RTC, RtcConfig::default());
+    static RTC: StaticCell<Rtc> = StaticCell::new();
+    let rtc = RTC.init(rtc);
+    embassy_stm32::low_power::stop_with_rtc(rtc);
+
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    info!("Low-power blink started");
+
+    // Expected MCU current (STM32F401RE datasheet, typical at 25 C, measured
+    // on the IDD jumper JP6 of the Nucleo):
+    //   awake with the default 16 MHz HSI clock: ~4 mA
+    //   STOP mode, main regulator on:            ~0.1 mA
+    // The LED itself draws a few mA while lit, so it is only pulsed briefly.
+    loop {
+        led.set_high();
+        Timer::after_millis(50).await;
+        led.set_low();
+        // Waits longer than 250 ms let the executor enter STOP mode
+        Timer::after_secs(3).await;
+    }
+}
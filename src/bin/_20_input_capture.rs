@@ -0,0 +1,100 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 21: Input Capture                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::time::{hz, Hertz};
+use embassy_stm32::timer::input_capture::{CapturePin, InputCapture};
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_stm32::timer::{self, Channel};
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_time::{with_timeout, Duration};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    TIM1_CC => timer::CaptureCompareInterruptHandler<peripherals::TIM1>;
+});
+
+// Capture timer tick rate. At 1 MHz the 16-bit TIM1 counter wraps every
+// 65.536 ms, so signals down to about 16 Hz can be measured.
+const TICK_FREQ: Hertz = Hertz(1_000_000);
+
+// Give up waiting for a full cycle after this long. Waiting for the first
+// rising edge, then the falling and the next rising edge can take almost two
+// periods: 2 x 65.536 ms at the slowest measurable frequency.
+const EDGE_TIMEOUT: Duration = Duration::from_millis(150);
+
+// Convert a number of timer ticks to microseconds
+fn ticks_to_us(ticks: u16) -> u32 {
+    (ticks as u64 * 1_000_000 / TICK_FREQ.0 as u64) as u32
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Test signal: 1 kHz, 25% duty on PB4 (TIM3_CH1, D5).
+    // Connect D5 to D7 to measure it, or feed D7 from an external source.
+    let test_pin = PwmPin::new_ch1(p.PB4, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM3, Some(test_pin), None, None, None, hz(1_000), Default::default());
+    let mut test_signal = pwm.ch1();
+    test_signal.set_duty_cycle_percent(25);
+    test_signal.enable();
+
+    // Signal under test on PA8 (TIM1_CH1, D7)
+    let capture_pin = CapturePin::new_ch1(p.PA8, Pull::None);
+    let mut capture = InputCapture::new(p.TIM1, Some(capture_pin), None, None, None, Irqs, TICK_FREQ, Default::default());
+
+    loop {
+        // One cycle: rising edge, falling edge, next rising edge
+        let cycle = with_timeout(EDGE_TIMEOUT, async {
+            let start = capture.wait_for_rising_edge(Channel::Ch1).await as u16;
+            let fall = capture.wait_for_falling_edge(Channel::Ch1).await as u16;
+            let end = capture.wait_for_rising_edge(Channel::Ch1).await as u16;
+            // Wrapping differences stay correct across a counter overflow
+            (end.wrapping_sub(start), fall.wrapping_sub(start))
+        })
+        .await;
+
+        match cycle {
+            Ok((period, high)) if period > 0 => {
+                let period_us = ticks_to_us(period);
+                let high_us = ticks_to_us(high);
+                let frequency_hz = 1_000_000 / period_us.max(1);
+                let duty = high_us as f32 * 100.0 / period_us as f32;
+                info!(
+                    "period {} us, high {} us, frequency {} Hz, duty {}%",
+                    period_us, high_us, frequency_hz, duty
+                );
+            }
+            Ok(_) => warn!("signal too fast to measure"),
+            Err(_) => warn!("no edges detected"),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 22: Quadrature Encoder               *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::timer::qei::{Qei, QeiPin};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::encoder::Encoder;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Encoder A on PA0 (TIM2_CH1, A0), B on PA1 (TIM2_CH2, A1).
+    // The pins have no internal pull-ups: use an encoder module with pull-up
+    // resistors or add 10k resistors to 3.3V.
+    let ch_a = QeiPin::new_ch1(p.PA0);
+    let ch_b = QeiPin::new_ch2(p.PA1);
+    let mut encoder = Encoder::new(Qei::new(p.TIM2, ch_a, ch_b));
+
+    let mut last_position = encoder.position();
+    info!("Turn the encoder");
+
+    loop {
+        let position = encoder.position();
+        if position != last_position {
+            let direction = if position > last_position {
+                "clockwise"
+            } else {
+                "counter-clockwise"
+            };
+            info!("position {} ({})", position, direction);
+            last_position = position;
+        }
+        Timer::after_millis(10).await;
+    }
+}
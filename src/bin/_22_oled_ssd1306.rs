@@ -0,0 +1,93 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 23: SSD1306 OLED Display             *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::i2c::I2c;
+use embassy_stm32::time::khz;
+use embassy_time::Timer;
+use embedded_graphics::mono_font::ascii::{FONT_6X10, FONT_9X15};
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::text::{Baseline, Text};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use heapless::String;
+use ssd1306::prelude::*;
+use ssd1306::{I2CDisplayInterface, Ssd1306};
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14)
+    let i2c = I2c::new_blocking(p.I2C1, p.PB8, p.PB9, khz(400), Default::default());
+
+    // 128x64 display at the default address 0x3C, drawn through a RAM buffer
+    let interface = I2CDisplayInterface::new(i2c);
+    let mut display =
+        Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0).into_buffered_graphics_mode();
+
+    // A missing or miswired display fails here: keep retrying so it can be
+    // connected without resetting the board
+    while let Err(e) = display.init() {
+        warn!("Display init failed: {}, retrying", Debug2Format(&e));
+        Timer::after_secs(1).await;
+    }
+    info!("Display ready");
+
+    let title_style = MonoTextStyle::new(&FONT_9X15, BinaryColor::On);
+    let text_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+
+    let mut counter: u32 = 0;
+    let mut text: String<20> = String::new();
+
+    loop {
+        display.clear_buffer();
+
+        // Drawing into the buffer can't fail, only flush talks to the display
+        Text::with_baseline("Hello Embassy", Point::new(0, 0), title_style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        text.clear();
+        core::write!(&mut text, "Counter: {}", counter).unwrap();
+        Text::with_baseline(&text, Point::new(0, 32), text_style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        if let Err(e) = display.flush() {
+            warn!("Display update failed: {}", Debug2Format(&e));
+        }
+
+        counter = counter.wrapping_add(1);
+        Timer::after_millis(500).await;
+    }
+}
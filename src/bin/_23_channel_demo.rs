@@ -0,0 +1,125 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 24: Channel Message Passing          *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Pull, Speed};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_time::{Duration, Instant, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+/// Commands understood by the LED task.
+#[derive(Clone, Copy, Format)]
+enum BlinkCommand {
+    SpeedUp,
+    SpeedDown,
+    Reset,
+}
+
+// Bounded queue: at most 4 commands wait for the LED task
+static COMMANDS: Channel<CriticalSectionRawMutex, BlinkCommand, 4> = Channel::new();
+
+const DEFAULT_DELAY_MS: u64 = 1000;
+const MIN_DELAY_MS: u64 = 50;
+const MAX_DELAY_MS: u64 = 2000;
+
+// Holding the button longer than this sends Reset instead of SpeedUp
+const LONG_PRESS: Duration = Duration::from_secs(1);
+
+async fn send(command: BlinkCommand) {
+    // A full channel makes the sender wait instead of dropping the command
+    if COMMANDS.try_send(command).is_err() {
+        warn!("Channel full, waiting for the LED task");
+        COMMANDS.send(command).await;
+    }
+    info!("Sent {}", command);
+}
+
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    loop {
+        button.wait_for_press().await;
+        let pressed_at = Instant::now();
+        button.wait_for_release().await;
+
+        if pressed_at.elapsed() >= LONG_PRESS {
+            send(BlinkCommand::Reset).await;
+        } else {
+            send(BlinkCommand::SpeedUp).await;
+        }
+    }
+}
+
+#[embassy_executor::task]
+async fn decay_task() {
+    // A second producer on the same channel: slowly undo the speed-ups
+    loop {
+        Timer::after_secs(5).await;
+        send(BlinkCommand::SpeedDown).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    let mut delay_ms = DEFAULT_DELAY_MS;
+
+    loop {
+        Timer::after_millis(delay_ms).await;
+        led.toggle();
+
+        // Commands are only handled between toggles, so pressing the button
+        // quickly fills the channel and shows the backpressure
+        while let Ok(command) = COMMANDS.try_receive() {
+            delay_ms = match command {
+                BlinkCommand::SpeedUp => (delay_ms / 2).max(MIN_DELAY_MS),
+                BlinkCommand::SpeedDown => (delay_ms * 2).min(MAX_DELAY_MS),
+                BlinkCommand::Reset => DEFAULT_DELAY_MS,
+            };
+            info!("Handled {}, blink delay {} ms", command, delay_ms);
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Press to speed up, hold for 1 s to reset");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+    spawner.spawn(button_task(button)).unwrap();
+    spawner.spawn(decay_task()).unwrap();
+}
@@ -0,0 +1,69 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 25: PWM Analog Output                *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::waveform::{sine_table, TableStepper};
+use {defmt_rtt as _, panic_probe as _};
+
+// Samples per sine period in the lookup table
+const TABLE_SIZE: usize = 64;
+// Rate at which the duty cycle is updated
+const SAMPLE_RATE_HZ: u64 = 1_000;
+// Frequency of the synthesized sine wave
+const SINE_FREQUENCY_HZ: f32 = 5.0;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // PWM carrier on PA8 (TIM1_CH1, D7). Filter it with R = 1 kOhm in series
+    // and C = 1 uF to GND: the 159 Hz cut-off passes the 5 Hz sine and
+    // attenuates the 20 kHz carrier about 125 times.
+    let out_pin = PwmPin::new_ch1(p.PA8, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM1, Some(out_pin), None, None, None, khz(20), Default::default());
+    let mut out = pwm.ch1();
+    out.enable();
+
+    // The table holds duty cycle values, so it follows the PWM resolution
+    let max_duty = out.max_duty_cycle();
+    let table: [u16; TABLE_SIZE] = sine_table(max_duty);
+    let mut stepper = TableStepper::new(SINE_FREQUENCY_HZ, SAMPLE_RATE_HZ as f32);
+    info!("Max duty {}, {} Hz sine", max_duty, SINE_FREQUENCY_HZ);
+
+    // A ticker keeps a fixed sample period, whatever the time spent in the loop
+    let mut ticker = Ticker::every(Duration::from_hz(SAMPLE_RATE_HZ));
+    loop {
+        out.set_duty_cycle(stepper.next(&table));
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,56 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 26: Software PWM                     *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::soft_pwm::{set_duty, soft_pwm_task};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Fast enough to avoid visible flicker, slow enough for the timer granularity
+const PWM_FREQUENCY_HZ: u32 = 100;
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // PA5 also has a timer channel, but the soft PWM works on any output pin
+    let led = Output::new(board.user_led, Level::Low, Speed::Low);
+    spawner.spawn(soft_pwm_task(led, PWM_FREQUENCY_HZ)).unwrap();
+
+    loop {
+        // Fade in, then fade out, in 1% steps
+        for percent in (0..=100).chain((0..100).rev()) {
+            set_duty(percent as f32 / 100.0);
+            Timer::after_millis(10).await;
+        }
+    }
+}
@@ -0,0 +1,79 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 27: Hardware CRC                     *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::crc::Crc;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+// The F401 CRC unit computes CRC-32/MPEG-2: polynomial 0x04C11DB7, initial
+// value 0xFFFFFFFF, no bit reflection, no final XOR. It is not the zlib CRC32.
+const DATA: &[u8; 16] = b"Hello, Embassy!!";
+const EXPECTED: u32 = 0xF59B_47F7;
+
+// Feed bytes to the CRC unit, which only accepts 32-bit words.
+// Bytes are packed most significant first, matching a byte-wise CRC-32/MPEG-2.
+fn feed_bytes(crc: &mut Crc, data: &[u8]) -> u32 {
+    assert!(data.len() % 4 == 0, "the CRC unit takes whole 32-bit words");
+    for chunk in data.chunks_exact(4) {
+        crc.feed_word(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+    }
+    crc.read()
+}
+
+fn check(name: &str, result: u32) {
+    if result == EXPECTED {
+        info!("{}: {=u32:#010x} PASS", name, result);
+    } else {
+        error!("{}: {=u32:#010x} FAIL, expected {=u32:#010x}", name, result, EXPECTED);
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // The unit starts from 0xFFFFFFFF
+    let mut crc = Crc::new(p.CRC);
+
+    // Whole buffer at once
+    check("single pass", feed_bytes(&mut crc, DATA));
+
+    // Reset before a new computation, otherwise it continues from the last result
+    crc.reset();
+
+    // Same data in two chunks: the unit keeps its state between feeds, so
+    // data received piece by piece can be checked incrementally
+    let (first, second) = DATA.split_at(8);
+    feed_bytes(&mut crc, first);
+    check("two chunks", feed_bytes(&mut crc, second));
+
+    // Without a reset the previous result becomes the starting value
+    let continued = feed_bytes(&mut crc, DATA);
+    info!("without reset: {=u32:#010x} (differs as expected)", continued);
+}
@@ -0,0 +1,101 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 28: Flash Storage                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::flash::{Blocking, Error, Flash, WRITE_SIZE};
+use embassy_stm32::gpio::Pull;
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Sector 7, the last 128 KiB of the 512 KiB flash (0x0806_0000..0x0808_0000).
+// Code is placed from the start of flash, so it never reaches this sector
+// unless the binary grows beyond 384 KiB. Offsets are relative to 0x0800_0000.
+const SECTOR_START: u32 = 0x6_0000;
+const SECTOR_END: u32 = 0x8_0000;
+
+// Marks a valid record: erased flash reads as 0xFF
+const MAGIC: u32 = 0xB007_C0DE;
+
+// Magic and counter, padded to a whole number of flash write units
+const RECORD_SIZE: usize = 16;
+const _: () = assert!(RECORD_SIZE % WRITE_SIZE == 0);
+
+fn read_counter(flash: &mut Flash<'_, Blocking>) -> Result<Option<u32>, Error> {
+    let mut record = [0u8; RECORD_SIZE];
+    flash.blocking_read(SECTOR_START, &mut record)?;
+
+    let magic = u32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+    let counter = u32::from_le_bytes([record[4], record[5], record[6], record[7]]);
+    Ok((magic == MAGIC).then_some(counter))
+}
+
+fn write_counter(flash: &mut Flash<'_, Blocking>, counter: u32) -> Result<(), Error> {
+    let mut record = [0xFFu8; RECORD_SIZE];
+    record[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+    record[4..8].copy_from_slice(&counter.to_le_bytes());
+
+    // Programming can only clear bits, so the sector is erased (all bits set) first
+    flash.blocking_erase(SECTOR_START, SECTOR_END)?;
+    flash.blocking_write(SECTOR_START, &record)
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    let mut flash = Flash::new_blocking(p.FLASH);
+
+    let stored = match read_counter(&mut flash) {
+        Ok(stored) => stored.unwrap_or(0),
+        Err(e) => {
+            error!("Flash read failed: {}", e);
+            0
+        }
+    };
+    let boot = stored + 1;
+    info!("Boot #{}", boot);
+    info!("Press the button to save the counter, then reset the board");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    loop {
+        // Each save erases the sector, which is rated for about 10 000 cycles:
+        // write on demand, never in a loop
+        button.wait_for_press().await;
+
+        // Erasing a 128 KiB sector blocks the CPU for about a second
+        match write_counter(&mut flash, boot) {
+            Ok(()) => info!("Saved boot counter {}", boot),
+            Err(e) => error!("Flash write failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 29: Pseudo-Random Numbers            *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_time::{Instant, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::rng::XorShift32;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Seed from the noise of a floating analog input: leave A0 (PA0) unconnected.
+    // The shortest sample time keeps the most noise in the low bits.
+    let mut adc = Adc::new(p.ADC1);
+    adc.set_sample_time(SampleTime::CYCLES3);
+    let mut floating = p.PA0;
+
+    let mut seed: u32 = 0;
+    for _ in 0..64 {
+        let sample = adc.blocking_read(&mut floating) as u32;
+        seed = seed.rotate_left(5) ^ sample;
+    }
+    // The boot time varies a little with the clock start-up and the debugger
+    seed ^= Instant::now().as_ticks() as u32;
+    info!("Seed {=u32:#010x}", seed);
+
+    let mut rng = XorShift32::new(seed);
+
+    // ST-LINK virtual COM port
+    let mut usart = uart_or_halt(Uart::new_blocking(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Config::default(),
+    ));
+
+    let mut bytes = [0u8; 16];
+    let mut line: String<48> = String::new();
+
+    loop {
+        rng.fill_bytes(&mut bytes);
+
+        line.clear();
+        for byte in bytes {
+            core::write!(&mut line, "{:02x}", byte).unwrap();
+        }
+        line.push_str("\r\n").unwrap();
+
+        if let Err(e) = usart.blocking_write(line.as_bytes()) {
+            log_uart_error(e);
+        }
+        Timer::after_millis(500).await;
+    }
+}
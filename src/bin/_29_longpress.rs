@@ -0,0 +1,100 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 30: Long Press Detection             *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Pull, Speed};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, PressKind, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+const LONG_PRESS: Duration = Duration::from_millis(800);
+
+#[derive(Clone, Copy, PartialEq, Eq, Format)]
+enum LedMode {
+    Off,
+    On,
+    Blinking,
+}
+
+static LED_MODE: Signal<CriticalSectionRawMutex, LedMode> = Signal::new();
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    let mut mode = LedMode::Off;
+
+    loop {
+        match mode {
+            LedMode::Off => led.set_low(),
+            LedMode::On => led.set_high(),
+            LedMode::Blinking => led.toggle(),
+        }
+
+        // Steady modes just wait for the next change; blinking also wakes up to toggle
+        mode = if mode == LedMode::Blinking {
+            match select(LED_MODE.wait(), Timer::after_millis(100)).await {
+                Either::First(new_mode) => new_mode,
+                Either::Second(()) => mode,
+            }
+        } else {
+            LED_MODE.wait().await
+        };
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Short press: toggle the LED, long press: blink");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+
+    let mut mode = LedMode::Off;
+    loop {
+        let kind = button.wait_for_press_kind(LONG_PRESS).await;
+
+        mode = match (kind, mode) {
+            (PressKind::Long, _) => LedMode::Blinking,
+            (PressKind::Short, LedMode::Off) => LedMode::On,
+            // A short press turns the LED off, also when blinking
+            (PressKind::Short, _) => LedMode::Off,
+        };
+        info!("{} press, LED {}", kind, mode);
+        LED_MODE.signal(mode);
+    }
+}
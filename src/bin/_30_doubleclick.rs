@@ -0,0 +1,91 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 31: Double Click Detection           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Pull, Speed};
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Maximum pause between the first release and the second press
+const DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(300);
+
+// false: slow even blink, true: double flash
+static DOUBLE_FLASH: AtomicBool = AtomicBool::new(false);
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+
+    // The pattern is read at the start of each one-second cycle
+    loop {
+        if DOUBLE_FLASH.load(Ordering::Relaxed) {
+            for _ in 0..2 {
+                led.set_high();
+                Timer::after_millis(100).await;
+                led.set_low();
+                Timer::after_millis(100).await;
+            }
+            Timer::after_millis(600).await;
+        } else {
+            led.set_high();
+            Timer::after_millis(500).await;
+            led.set_low();
+            Timer::after_millis(500).await;
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Double click to change the blink pattern");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+
+    loop {
+        // Each call starts a fresh attempt with a new window
+        if button.wait_for_double_click(DOUBLE_CLICK_WINDOW).await {
+            let double_flash = !DOUBLE_FLASH.load(Ordering::Relaxed);
+            DOUBLE_FLASH.store(double_flash, Ordering::Relaxed);
+            let pattern = if double_flash { "double flash" } else { "slow blink" };
+            info!("Double click, pattern: {}", pattern);
+        } else {
+            info!("Single click, pattern unchanged");
+        }
+    }
+}
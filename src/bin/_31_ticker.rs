@@ -0,0 +1,72 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 32: Drift-Free Ticker                *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{block_for, Duration, Instant, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+const PERIOD: Duration = Duration::from_millis(100);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    // _00_blinky_led waits with Timer::after, which starts counting when the
+    // loop body is done: every iteration lasts "work + delay", so the loop
+    // slowly falls behind the wall clock. A Ticker schedules each tick at
+    // start + n * PERIOD instead, so time spent working is absorbed and the
+    // error never accumulates.
+    let mut ticker = Ticker::every(PERIOD);
+    let start = Instant::now();
+    let mut tick: u64 = 0;
+
+    loop {
+        ticker.next().await;
+        tick += 1;
+
+        let elapsed = start.elapsed().as_micros();
+        let expected = tick * PERIOD.as_micros();
+        info!(
+            "tick {}: elapsed {} us, expected {} us, error {} us",
+            tick,
+            elapsed,
+            expected,
+            elapsed as i64 - expected as i64
+        );
+
+        led.toggle();
+
+        // Simulated work of varying length (0-45 ms). With Timer::after this
+        // would add up to seconds of drift within a minute.
+        block_for(Duration::from_millis(tick % 10 * 5));
+    }
+}
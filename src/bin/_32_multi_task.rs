@@ -0,0 +1,65 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 33: Concurrent Blink Tasks           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// pool_size reserves room for three instances of the same task
+#[embassy_executor::task(pool_size = 3)]
+async fn led_task(led: AnyPin, frequency_hz: u64) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+
+    // Two toggles per blink
+    let mut ticker = Ticker::every(Duration::from_hz(2 * frequency_hz));
+    loop {
+        led.toggle();
+        ticker.next().await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // On-board LED LD2 on PA5, external LEDs (with ~330 Ohm resistors to GND)
+    // on PA10 (D2) and PB5 (D4)
+    spawner.spawn(led_task(board.user_led.degrade(), 1)).unwrap();
+    spawner.spawn(led_task(p.PA10.degrade(), 3)).unwrap();
+    spawner.spawn(led_task(p.PB5.degrade(), 7)).unwrap();
+
+    // No threads are involved: each task runs until its next .await, then
+    // hands control back to the executor, which polls whichever task's timer
+    // has expired. Waiting costs nothing, so the three tasks interleave on a
+    // single core and none of them delays the others.
+    info!("Three tasks blinking at 1, 3 and 7 Hz");
+}
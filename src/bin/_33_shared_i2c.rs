@@ -0,0 +1,104 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 34: Shared I2C Bus                   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_embedded_hal::shared_bus::asynch::i2c::I2cDevice;
+use embassy_executor::Spawner;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::mode::Async;
+use embassy_stm32::time::khz;
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_time::{Duration, Ticker};
+use embedded_hal_async::i2c::I2c as _;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::{hts221, lsm6dsl};
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+type I2cBus = Mutex<CriticalSectionRawMutex, I2c<'static, Async>>;
+
+// Both sensors of the X-NUCLEO-IKS01A2 shield answer on register 0x0F
+const WHO_AM_I: u8 = 0x0F;
+
+#[embassy_executor::task(pool_size = 2)]
+async fn sensor_task(
+    mut i2c: I2cDevice<'static, CriticalSectionRawMutex, I2c<'static, Async>>,
+    name: &'static str,
+    address: u8,
+    period_ms: u64,
+) {
+    let mut ticker = Ticker::every(Duration::from_millis(period_ms));
+    loop {
+        // The device locks the bus for the whole write-then-read transaction,
+        // so the other task can't slip its own transfer in between
+        let mut id = [0u8; 1];
+        match i2c.write_read(address, &[WHO_AM_I], &mut id).await {
+            Ok(()) => info!("{}: WHO_AM_I = {=u8:#04x}", name, id[0]),
+            Err(e) => warn!("{}: I2C error {}", name, e),
+        }
+        ticker.next().await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14)
+    let i2c = I2c::new(
+        p.I2C1,
+        p.PB8,
+        p.PB9,
+        Irqs,
+        p.DMA1_CH7,
+        p.DMA1_CH0,
+        khz(100),
+        Default::default(),
+    );
+
+    // The bus lives in a static so tasks can borrow it for 'static
+    static I2C_BUS: StaticCell<I2cBus> = StaticCell::new();
+    let i2c_bus = I2C_BUS.init(Mutex::new(i2c));
+
+    // One I2cDevice per driver, all sharing the same bus
+    let hts221_device = I2cDevice::new(i2c_bus);
+    let lsm6dsl_device = I2cDevice::new(i2c_bus);
+
+    spawner
+        .spawn(sensor_task(hts221_device, "HTS221", hts221::ADDRESS, 1000))
+        .unwrap();
+    spawner
+        .spawn(sensor_task(lsm6dsl_device, "LSM6DSL", lsm6dsl::ADDRESS_SA0_HIGH, 300))
+        .unwrap();
+}
@@ -0,0 +1,80 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 35: HTS221 Sensor                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::time::khz;
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::hts221::{Error, Hts221};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14)
+    let i2c = I2c::new(
+        p.I2C1,
+        p.PB8,
+        p.PB9,
+        Irqs,
+        p.DMA1_CH7,
+        p.DMA1_CH0,
+        khz(100),
+        Default::default(),
+    );
+
+    let mut sensor = match Hts221::new(i2c).await {
+        Ok(sensor) => sensor,
+        Err(Error::WrongDevice(id)) => {
+            error!("WHO_AM_I returned {=u8:#04x}, expected 0xbc: is this an HTS221?", id);
+            return;
+        }
+        Err(Error::I2c(e)) => {
+            error!("HTS221 not responding: {}", e);
+            return;
+        }
+    };
+    info!("HTS221 found");
+
+    let mut ticker = Ticker::every(Duration::from_secs(1));
+    loop {
+        ticker.next().await;
+        match sensor.read().await {
+            Ok(m) => info!("Temperature: {} C, humidity: {} %", m.temperature, m.humidity),
+            Err(e) => warn!("Read failed: {}", e),
+        }
+    }
+}
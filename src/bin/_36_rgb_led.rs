@@ -0,0 +1,131 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 37: RGB LED Color Fade               *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::peripherals::TIM1;
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm, SimplePwmChannel};
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use getting_started_embassy_stm32f401re::color::{blend, hsv_to_rgb};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::waveform::gamma;
+use {defmt_rtt as _, panic_probe as _};
+
+// Rate at which the color is updated while fading
+const UPDATE_PERIOD: Duration = Duration::from_millis(10);
+// Colors are mixed in perceptual brightness levels, gamma corrected on output
+const MAX_LEVEL: u16 = 255;
+
+/// Common-cathode RGB LED on three channels of the same timer.
+struct RgbLed {
+    red: SimplePwmChannel<'static, TIM1>,
+    green: SimplePwmChannel<'static, TIM1>,
+    blue: SimplePwmChannel<'static, TIM1>,
+    max_duty: u16,
+    current: [u16; 3],
+}
+
+impl RgbLed {
+    fn set(&mut self, color: [u16; 3]) {
+        let max_duty = self.max_duty;
+        let duty = |level: u16| gamma(level.min(MAX_LEVEL) as u8, max_duty);
+        // Common cathode: a higher duty cycle means a brighter color
+        self.red.set_duty_cycle(duty(color[0]));
+        self.green.set_duty_cycle(duty(color[1]));
+        self.blue.set_duty_cycle(duty(color[2]));
+        self.current = color;
+    }
+
+    /// Fade from the current color to `target` over `duration`.
+    async fn fade_to(&mut self, target: [u16; 3], duration: Duration) {
+        let from = self.current;
+        let start = Instant::now();
+        let mut ticker = Ticker::every(UPDATE_PERIOD);
+        loop {
+            let elapsed = start.elapsed();
+            if elapsed >= duration {
+                break;
+            }
+            let t = elapsed.as_micros() as f32 / duration.as_micros() as f32;
+            self.set(blend(from, target, t));
+            ticker.next().await;
+        }
+        self.set(target);
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Red on PA8 (TIM1_CH1, D7), green on PA9 (TIM1_CH2, D8), blue on PA10
+    // (TIM1_CH3, D2), each through a 220 Ohm resistor; the cathode goes to GND
+    let red = PwmPin::new_ch1(p.PA8, OutputType::PushPull);
+    let green = PwmPin::new_ch2(p.PA9, OutputType::PushPull);
+    let blue = PwmPin::new_ch3(p.PA10, OutputType::PushPull);
+    let pwm = SimplePwm::new(
+        p.TIM1,
+        Some(red),
+        Some(green),
+        Some(blue),
+        None,
+        khz(1),
+        Default::default(),
+    );
+
+    // Splitting gives each color its own independently owned channel
+    let mut channels = pwm.split();
+    channels.ch1.enable();
+    channels.ch2.enable();
+    channels.ch3.enable();
+    let max_duty = channels.ch1.max_duty_cycle();
+    let mut led = RgbLed {
+        red: channels.ch1,
+        green: channels.ch2,
+        blue: channels.ch3,
+        max_duty,
+        current: [0; 3],
+    };
+    led.set([0; 3]);
+
+    // Fade in to a target color, hold it, then go around the hue wheel
+    let orange = hsv_to_rgb(30.0, 1.0, 1.0, MAX_LEVEL);
+    info!("Fading to orange {}", orange);
+    led.fade_to(orange, Duration::from_secs(2)).await;
+    Timer::after_secs(1).await;
+
+    let mut hue = 30.0;
+    let mut ticker = Ticker::every(UPDATE_PERIOD);
+    loop {
+        // One degree every 10 ms: a full turn takes 3.6 s
+        hue = (hue + 1.0) % 360.0;
+        led.set(hsv_to_rgb(hue, 1.0, 1.0, MAX_LEVEL));
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,55 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 38: HC-SR04 Distance Sensor          *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::hcsr04::HcSr04;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Trigger on PA9 (D8), echo on PA8 (D7). The sensor runs at 5 V: both
+    // pins are 5 V tolerant, so the echo can be connected directly.
+    let trigger = Output::new(p.PA9, Level::Low, Speed::Low);
+    let echo = ExtiInput::new(p.PA8, p.EXTI8, Pull::Down);
+    let mut sensor = HcSr04::new(trigger, echo);
+
+    let mut ticker = Ticker::every(Duration::from_millis(100));
+    loop {
+        match sensor.measure_cm().await {
+            Some(cm) => info!("Distance: {} cm", cm),
+            None => warn!("No echo: nothing in range or sensor not connected"),
+        }
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,76 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 39: WS2812 LED Strip                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::spi::{self, Spi};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::clock::max_performance_config;
+use getting_started_embassy_stm32f401re::color::hsv_to_rgb;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::ws2812::{self, Rgb, Ws2812};
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+// Number of LEDs in the strip
+const LEDS: usize = 8;
+// WS2812 are very bright: keep the brightness low to save eyes and current
+const BRIGHTNESS: f32 = 0.1;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    // 84 MHz on APB2, which clocks SPI1, so that 84 MHz / 32 gives the SPI
+    // frequency expected by the ws2812 module
+    let p = embassy_stm32::init(max_performance_config());
+    info!("Hello World!");
+
+    // Only MOSI is needed: data in on PA7 (SPI1_MOSI, D11). Power the strip
+    // from 5 V with a common GND.
+    let mut spi_config = spi::Config::default();
+    spi_config.frequency = ws2812::SPI_FREQUENCY;
+    let spi = Spi::new_txonly_nosck(p.SPI1, p.PA7, p.DMA2_CH3, spi_config);
+
+    static BUFFER: StaticCell<[u8; ws2812::buffer_size(LEDS)]> = StaticCell::new();
+    let buffer = BUFFER.init([0; ws2812::buffer_size(LEDS)]);
+    let mut strip = Ws2812::new(spi, buffer);
+
+    // A rainbow spread over the strip, rotating along it
+    let mut offset = 0.0;
+    let mut colors = [Rgb::default(); LEDS];
+    let mut ticker = Ticker::every(Duration::from_millis(20));
+    loop {
+        for (i, color) in colors.iter_mut().enumerate() {
+            let hue = offset + i as f32 * 360.0 / LEDS as f32;
+            let [r, g, b] = hsv_to_rgb(hue, 1.0, BRIGHTNESS, 255);
+            *color = Rgb::new(r as u8, g as u8, b as u8);
+        }
+        if let Err(e) = strip.write(&colors).await {
+            warn!("SPI error {}", e);
+        }
+        offset = (offset + 2.0) % 360.0;
+        ticker.next().await;
+    }
+}
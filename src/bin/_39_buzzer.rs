@@ -0,0 +1,83 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 40: Buzzer Melody                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_stm32::timer::Channel;
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::music::notes::*;
+use getting_started_embassy_stm32f401re::music::play_tone;
+use {defmt_rtt as _, panic_probe as _};
+
+// Length of an eighth note (120 quarter notes per minute)
+const EIGHTH_MS: u64 = 250;
+// Short silence after each note, so repeated notes don't merge into one
+const GAP: Duration = Duration::from_millis(30);
+
+// "Ode to Joy": (frequency, length in eighth notes)
+const MELODY: [(u32, u64); 16] = [
+    (E4, 2),
+    (E4, 2),
+    (F4, 2),
+    (G4, 2),
+    (G4, 2),
+    (F4, 2),
+    (E4, 2),
+    (D4, 2),
+    (C4, 2),
+    (C4, 2),
+    (D4, 2),
+    (E4, 2),
+    (E4, 3),
+    (D4, 1),
+    (D4, 4),
+    (REST, 4),
+];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Passive buzzer between PB4 (TIM3_CH1, D5) and GND. An active buzzer
+    // beeps at its own fixed pitch and can't play notes.
+    let buzzer_pin = PwmPin::new_ch1(p.PB4, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM3, Some(buzzer_pin), None, None, None, hz(C4), Default::default());
+
+    loop {
+        info!("Playing");
+        for (frequency, eighths) in MELODY {
+            let length = Duration::from_millis(EIGHTH_MS * eighths);
+            play_tone(&mut pwm, Channel::Ch1, frequency, length - GAP).await;
+            Timer::after(GAP).await;
+        }
+        Timer::after_secs(2).await;
+    }
+}
@@ -0,0 +1,127 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 41: USB Serial                       *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_stm32::bind_interrupts;
+use embassy_stm32::peripherals::USB_OTG_FS;
+use embassy_stm32::usb::{self, Driver};
+use embassy_usb::class::cdc_acm::{CdcAcmClass, State};
+use embassy_usb::driver::EndpointError;
+use embassy_usb::Builder;
+use getting_started_embassy_stm32f401re::clock::max_performance_config;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    OTG_FS => usb::InterruptHandler<USB_OTG_FS>;
+});
+
+// Largest packet of a full-speed bulk endpoint
+const MAX_PACKET_SIZE: u16 = 64;
+
+struct Disconnected;
+
+impl From<EndpointError> for Disconnected {
+    fn from(e: EndpointError) -> Self {
+        match e {
+            // Reads use a buffer of the full packet size, so it can't overflow
+            EndpointError::BufferOverflow => panic!("USB buffer overflow"),
+            EndpointError::Disabled => Disconnected,
+        }
+    }
+}
+
+/// Send back every packet received, until the host closes the port.
+async fn echo(class: &mut CdcAcmClass<'static, Driver<'static, USB_OTG_FS>>) -> Result<(), Disconnected> {
+    let mut buf = [0u8; MAX_PACKET_SIZE as usize];
+    loop {
+        let n = class.read_packet(&mut buf).await?;
+        let data = &buf[..n];
+        info!("Received {=[u8]:a}", data);
+        class.write_packet(data).await?;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    // USB full speed needs an exact 48 MHz clock, which the internal HSI
+    // (1% accuracy) can't provide. This configuration derives it from the
+    // 8 MHz ST-LINK clock, on the PLL Q output.
+    let p = embassy_stm32::init(max_performance_config());
+    info!("Hello World!");
+
+    // USB D+ on PA12 and D- on PA11 (CN10 pins 12 and 14). The board is
+    // powered by the ST-LINK, so VBUS is not monitored.
+    static EP_OUT_BUFFER: StaticCell<[u8; 256]> = StaticCell::new();
+    let mut usb_config = usb::Config::default();
+    usb_config.vbus_detection = false;
+    let driver = Driver::new_fs(
+        p.USB_OTG_FS,
+        Irqs,
+        p.PA12,
+        p.PA11,
+        EP_OUT_BUFFER.init([0; 256]),
+        usb_config,
+    );
+
+    // Test VID/PID: fine on your desk, not for a product
+    let mut config = embassy_usb::Config::new(0xc0de, 0xcafe);
+    config.manufacturer = Some("Perlatecnica");
+    config.product = Some("NucleoF401RE USB serial");
+    config.serial_number = Some("00000001");
+    config.max_power = 100;
+    config.max_packet_size_0 = 64;
+
+    // Descriptors and the CDC-ACM state must outlive the USB device
+    static CONFIG_DESCRIPTOR: StaticCell<[u8; 256]> = StaticCell::new();
+    static BOS_DESCRIPTOR: StaticCell<[u8; 256]> = StaticCell::new();
+    static CONTROL_BUF: StaticCell<[u8; 64]> = StaticCell::new();
+    static STATE: StaticCell<State> = StaticCell::new();
+    let mut builder = Builder::new(
+        driver,
+        config,
+        CONFIG_DESCRIPTOR.init([0; 256]),
+        BOS_DESCRIPTOR.init([0; 256]),
+        &mut [],
+        CONTROL_BUF.init([0; 64]),
+    );
+    let mut class = CdcAcmClass::new(&mut builder, STATE.init(State::new()), MAX_PACKET_SIZE);
+    let mut usb = builder.build();
+
+    // The USB stack and the echo loop run concurrently in the same task
+    let echo_loop = async {
+        loop {
+            class.wait_connection().await;
+            info!("Connected");
+            let _ = echo(&mut class).await;
+            info!("Disconnected");
+        }
+    };
+    join(usb.run(), echo_loop).await;
+}
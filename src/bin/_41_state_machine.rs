@@ -0,0 +1,58 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 42: Traffic Light State Machine      *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::fsm::LightState;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Red LED on PA10 (D2), yellow on PB3 (D3), green on PB5 (D4), each
+    // through a 220 Ohm resistor to GND
+    let mut lamps = [
+        Output::new(p.PA10, Level::Low, Speed::Low),
+        Output::new(p.PB3, Level::Low, Speed::Low),
+        Output::new(p.PB5, Level::Low, Speed::Low),
+    ];
+
+    let mut state = LightState::Red;
+    loop {
+        info!("{}", state);
+        // The outputs only mirror the state; all the logic is in LightState
+        for (lamp, on) in lamps.iter_mut().zip(state.lamps()) {
+            lamp.set_level(Level::from(on));
+        }
+        Timer::after_millis(state.duration_ms()).await;
+        state = state.next();
+    }
+}
@@ -0,0 +1,57 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 43: Clock Configuration              *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::peripherals::{TIM1, TIM2, USART1, USART2};
+use embassy_stm32::rcc::frequency;
+use embassy_time::{block_for, Duration};
+use getting_started_embassy_stm32f401re::clock::max_performance_config;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    // Instead of Default::default(), which keeps the 16 MHz internal HSI
+    let _p = embassy_stm32::init(max_performance_config());
+    info!("Hello World!");
+
+    // Peripheral kernel clocks as computed by embassy from the configuration
+    info!("APB1: {} Hz", frequency::<USART2>().0);
+    info!("APB1 timers: {} Hz", frequency::<TIM2>().0);
+    info!("APB2: {} Hz", frequency::<USART1>().0);
+    info!("APB2 timers: {} Hz", frequency::<TIM1>().0);
+
+    // Check the core clock by counting CPU cycles during one second of the
+    // embassy time base. It should read about 84 000 000.
+    let mut cp = unwrap!(cortex_m::Peripherals::take());
+    cp.DCB.enable_trace();
+    cp.DWT.enable_cycle_counter();
+    let start = cortex_m::peripheral::DWT::cycle_count();
+    block_for(Duration::from_secs(1));
+    let cycles = cortex_m::peripheral::DWT::cycle_count().wrapping_sub(start);
+    info!("Measured core clock: {} Hz", cycles);
+}
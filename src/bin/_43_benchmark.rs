@@ -0,0 +1,85 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 44: Benchmark                        *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::hint::black_box;
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::crc::Crc;
+use embassy_time::{Instant, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::time_it;
+use {defmt_rtt as _, panic_probe as _};
+
+// Operations shorter than a time base tick (about 30 us) are repeated this
+// many times and timed as a whole
+const RUNS: u32 = 100;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC1);
+    let mut pin = p.PA0;
+    let mut crc = Crc::new(p.CRC);
+    let data = [0xA5A5_A5A5u32; 256];
+
+    loop {
+        // Instant by hand: read the time before and after the code
+        let start = Instant::now();
+        let sample = adc.blocking_read(&mut pin);
+        let elapsed = start.elapsed();
+        info!("Single ADC read: {} us (sample {})", elapsed.as_micros(), sample);
+
+        // Too short to measure once: time a loop and divide
+        time_it!("100 ADC reads", {
+            for _ in 0..RUNS {
+                adc.blocking_read(&mut pin);
+            }
+        });
+
+        // CRC over 1 KB of data with the hardware unit
+        let checksum = time_it!("CRC over 1 KB", {
+            crc.reset();
+            crc.feed_words(&data)
+        });
+        info!("CRC = {=u32:#010x}", checksum);
+
+        // Plain computation on the FPU, for comparison. black_box stops the
+        // compiler from computing the result at build time.
+        let sum = time_it!("1000 float multiply-adds", {
+            let mut acc = 0.0f32;
+            for i in 0..1000 {
+                acc = acc * black_box(0.999) + i as f32;
+            }
+            acc
+        });
+        info!("Result {}", sum);
+
+        Timer::after_secs(2).await;
+    }
+}
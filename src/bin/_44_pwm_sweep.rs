@@ -0,0 +1,82 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 45: PWM Frequency Sweep              *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+const MIN_HZ: f32 = 100.0;
+const MAX_HZ: f32 = 2_000.0;
+// Each step changes the frequency by 5%: equal ratios sound like equal
+// pitch steps, so the sweep rises evenly to the ear
+const STEP_RATIO: f32 = 1.05;
+const STEP_PERIOD: Duration = Duration::from_millis(50);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Passive buzzer between PB4 (TIM3_CH1, D5) and GND, as in the buzzer
+    // example. A motor driver input works too.
+    let pin = PwmPin::new_ch1(p.PB4, OutputType::PushPull);
+    let start = hz(MIN_HZ as u32);
+    let mut pwm = SimplePwm::new(p.TIM3, Some(pin), None, None, None, start, Default::default());
+    pwm.ch1().enable();
+
+    let mut frequency = MIN_HZ;
+    let mut rising = true;
+    let mut ticker = Ticker::every(STEP_PERIOD);
+    loop {
+        // The driver stays alive: set_frequency rewrites the prescaler and
+        // auto-reload registers of the running timer
+        pwm.set_frequency(hz(frequency as u32));
+        // The duty cycle is a count compared with the auto-reload value, so
+        // it must be recomputed for the new period to stay at 50%
+        pwm.ch1().set_duty_cycle_fraction(1, 2);
+        info!("{} Hz (max duty {})", frequency as u32, pwm.max_duty_cycle());
+
+        if rising {
+            frequency *= STEP_RATIO;
+            if frequency >= MAX_HZ {
+                frequency = MAX_HZ;
+                rising = false;
+            }
+        } else {
+            frequency /= STEP_RATIO;
+            if frequency <= MIN_HZ {
+                frequency = MIN_HZ;
+                rising = true;
+            }
+        }
+        ticker.next().await;
+    }
+}
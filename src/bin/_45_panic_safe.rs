@@ -0,0 +1,91 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 46: Safe State on Panic              *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::panic::PanicInfo;
+
+use defmt::*;
+use defmt_rtt as _;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::peripherals::{TIM3, TIM4};
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::motor::HBridgeMotor;
+use getting_started_embassy_stm32f401re::safe_state::SafeStateGuard;
+use getting_started_embassy_stm32f401re::take_board;
+
+// The motor lives here instead of in main, so the panic handler can reach it
+static MOTOR: SafeStateGuard<HBridgeMotor<'static, TIM4, TIM3>> = SafeStateGuard::new();
+
+// Replaces panic_probe, which only logs and halts, leaving the PWM outputs
+// running with whatever duty cycle they had
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    // Stop the motor first: logging can take a while
+    let safe = MOTOR.make_safe();
+    error!("{}", Display2Format(info));
+    if safe {
+        error!("Motor stopped");
+    } else {
+        // The motor was in use when the panic happened (or never installed):
+        // resetting the MCU puts every pin back to a floating input
+        error!("Motor not reachable, resetting");
+        cortex_m::peripheral::SCB::sys_reset();
+    }
+    // Halt like panic_probe: the debugger stops here
+    cortex_m::asm::udf()
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    // Same wiring as _05_pwm_motor: IN1 on PB6 (TIM4_CH1, D10), IN2 on PA7 (TIM3_CH2, D11)
+    let in1_pin = PwmPin::new_ch1(p.PB6, OutputType::PushPull);
+    let in2_pin = PwmPin::new_ch2(p.PA7, OutputType::PushPull);
+    let pwm_in1 = SimplePwm::new(p.TIM4, Some(in1_pin), None, None, None, hz(100), Default::default());
+    let pwm_in2 = SimplePwm::new(p.TIM3, None, Some(in2_pin), None, None, hz(100), Default::default());
+
+    // split() gives 'static channels, which can be stored in a static
+    let motor = HBridgeMotor::new(pwm_in1.split().ch1, pwm_in2.split().ch2);
+    MOTOR.install(motor);
+
+    info!("Motor running at 60%, press the button to simulate a fault");
+    MOTOR.with(|motor| motor.set_speed(60));
+    button.wait_for_press().await;
+
+    // Any bug would do: an out-of-bounds index, an unwrap on None, ...
+    core::panic!("simulated fault while the motor is running");
+}
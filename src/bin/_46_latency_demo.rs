@@ -0,0 +1,123 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 47: Button Latency: Polling vs EXTI  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use cortex_m::peripheral::DWT;
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Core clock with the default configuration, to turn cycles into microseconds
+const CORE_MHZ: u32 = 16;
+// Interval between two checks of the input in polling mode
+const POLL_PERIOD: Duration = Duration::from_millis(10);
+
+// Cycle count at the moment the test edge was generated
+static EDGE_CYCLES: AtomicU32 = AtomicU32::new(0);
+// false: polling, true: EXTI interrupt
+static USE_EXTI: AtomicBool = AtomicBool::new(false);
+
+/// Generate a rising edge about every half second, recording when it happened.
+///
+/// A real button edge comes at an unknown time; generating it ourselves
+/// gives a precise starting point for the measurement.
+#[embassy_executor::task]
+async fn edge_task(mut out: Output<'static>) {
+    let mut n: u64 = 0;
+    loop {
+        // Vary the delay so that edges fall at different points between
+        // two polls, like button presses would
+        n += 1;
+        Timer::after_micros(400_000 + n * 3_700 % 10_000).await;
+        EDGE_CYCLES.store(DWT::cycle_count(), Ordering::Relaxed);
+        out.set_high();
+        Timer::after_millis(100).await;
+        out.set_low();
+    }
+}
+
+/// Switch between polling and EXTI at each user button press.
+#[embassy_executor::task]
+async fn mode_task(mut button: Debouncer<'static>) {
+    loop {
+        button.wait_for_press().await;
+        let exti = !USE_EXTI.load(Ordering::Relaxed);
+        USE_EXTI.store(exti, Ordering::Relaxed);
+        info!("Mode: {}", if exti { "EXTI interrupt" } else { "polling" });
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // The DWT cycle counter gives 1/16 us resolution, where an Instant only
+    // resolves about 30 us
+    let mut cp = unwrap!(cortex_m::Peripherals::take());
+    cp.DCB.enable_trace();
+    cp.DWT.enable_cycle_counter();
+
+    // Test edge out of PA10 (D2), wired to the input PB5 (D4)
+    let out = Output::new(p.PA10, Level::Low, Speed::Low);
+    let mut input = ExtiInput::new(p.PB5, p.EXTI5, Pull::Down);
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = Debouncer::new(
+        ExtiInput::new(board.user_button, board.button_exti, Pull::None),
+        ActiveLevel::Low,
+        DEFAULT_DEBOUNCE_TIME,
+    );
+    spawner.spawn(mode_task(button)).unwrap();
+    spawner.spawn(edge_task(out)).unwrap();
+
+    info!("Mode: polling, press the user button to switch");
+    loop {
+        if USE_EXTI.load(Ordering::Relaxed) {
+            // Interrupt: the EXTI line wakes the task as soon as the edge occurs
+            input.wait_for_rising_edge().await;
+        } else {
+            // Polling: the edge is only seen at the next check
+            while input.is_low() {
+                Timer::after(POLL_PERIOD).await;
+            }
+        }
+        led.toggle();
+        let cycles = DWT::cycle_count().wrapping_sub(EDGE_CYCLES.load(Ordering::Relaxed));
+        info!("Latency: {} us ({} cycles)", cycles / CORE_MHZ, cycles);
+
+        input.wait_for_low().await;
+    }
+}
@@ -0,0 +1,58 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 48: Smoothed ADC Reading             *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::adc_filter::{read_averaged, Ema};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+// Readings averaged by read_averaged
+const SAMPLES: u16 = 16;
+// EMA smoothing: lower is smoother but slower to follow the pot
+const ALPHA: f32 = 0.1;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Potentiometer wiper on PA0 (A0), ends on 3.3 V and GND, as in _07_adc_pot
+    let mut adc = Adc::new(p.ADC1);
+    let mut pin = p.PA0;
+    let mut ema = Ema::new(ALPHA);
+
+    let mut ticker = Ticker::every(Duration::from_hz(20));
+    loop {
+        let raw = adc.blocking_read(&mut pin);
+        let averaged = read_averaged(&mut adc, &mut pin, SAMPLES);
+        let smoothed = ema.update(raw);
+        info!("raw {} | average {} | EMA {}", raw, averaged, smoothed);
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,74 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 49: Potentiometer-Controlled Servo   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::adc_filter::{read_averaged, Ema};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::servo::{Servo, DEFAULT_MAX_US, DEFAULT_MIN_US};
+use {defmt_rtt as _, panic_probe as _};
+
+// Full scale of the 12-bit ADC
+const ADC_MAX: f32 = 4095.0;
+// Light smoothing: enough to stop the jitter, small enough to feel immediate
+const ALPHA: f32 = 0.3;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Potentiometer wiper on PA0 (A0), as in _07_adc_pot
+    let mut adc = Adc::new(p.ADC1);
+    let mut pot = p.PA0;
+    let mut ema = Ema::new(ALPHA);
+
+    // Servo signal on PA9 (TIM1_CH2, D8), as in _06_pwm_sg90
+    let ch2_pin = PwmPin::new_ch2(p.PA9, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM1, None, Some(ch2_pin), None, None, hz(50), Default::default());
+    let mut servo = Servo::new(pwm.ch2(), DEFAULT_MIN_US, DEFAULT_MAX_US);
+
+    // Servos take a new pulse every 20 ms: updating faster has no effect
+    let mut ticker = Ticker::every(Duration::from_hz(50));
+    let mut last_logged = u16::MAX;
+    loop {
+        let sample = ema.update(read_averaged(&mut adc, &mut pot, 8));
+        let angle = f32::from(sample) * 180.0 / ADC_MAX;
+        servo.set_angle(angle);
+
+        // Log only noticeable changes, not 50 lines per second
+        if sample.abs_diff(last_logged) > 20 {
+            info!("Pot {} -> {} degrees", sample, angle);
+            last_logged = sample;
+        }
+        ticker.next().await;
+    }
+}
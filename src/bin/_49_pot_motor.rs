@@ -0,0 +1,89 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 50: Potentiometer-Controlled Motor   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::adc_filter::{read_averaged, Ema};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::motor::HBridgeMotor;
+use {defmt_rtt as _, panic_probe as _};
+
+// Full scale of the 12-bit ADC
+const ADC_MAX: i32 = 4095;
+// Speeds within this many percent of zero stop the motor
+const DEADZONE: i32 = 8;
+
+/// Map a pot reading to a speed: -100 at one end, 0 in the middle, 100 at the other.
+///
+/// The speed range outside the deadzone is stretched back to 0..=100, so
+/// the motor starts gently when leaving it instead of jumping to 8%.
+fn pot_to_speed(sample: u16) -> i8 {
+    let centered = (i32::from(sample) * 200 / ADC_MAX - 100).clamp(-100, 100);
+    if centered.abs() <= DEADZONE {
+        return 0;
+    }
+    let magnitude = (centered.abs() - DEADZONE) * 100 / (100 - DEADZONE);
+    (magnitude * centered.signum()) as i8
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Potentiometer wiper on PA0 (A0), as in _07_adc_pot
+    let mut adc = Adc::new(p.ADC1);
+    let mut pot = p.PA0;
+    let mut ema = Ema::new(0.3);
+
+    // Same wiring as _05_pwm_motor: IN1 on PB6 (TIM4_CH1, D10), IN2 on PA7 (TIM3_CH2, D11)
+    let in1_pin = PwmPin::new_ch1(p.PB6, OutputType::PushPull);
+    let in2_pin = PwmPin::new_ch2(p.PA7, OutputType::PushPull);
+    let mut pwm_in1 = SimplePwm::new(p.TIM4, Some(in1_pin), None, None, None, hz(100), Default::default());
+    let mut pwm_in2 = SimplePwm::new(p.TIM3, None, Some(in2_pin), None, None, hz(100), Default::default());
+    let mut motor = HBridgeMotor::new(pwm_in1.ch1(), pwm_in2.ch2());
+
+    let mut last_speed = 0;
+    let mut ticker = Ticker::every(Duration::from_hz(20));
+    loop {
+        let sample = ema.update(read_averaged(&mut adc, &mut pot, 8));
+        let speed = pot_to_speed(sample);
+        // Negative speeds drive IN2 instead of IN1: the motor reverses as
+        // the pot crosses its midpoint
+        motor.set_speed(speed);
+
+        if speed != last_speed {
+            info!("Speed {}%", speed);
+            last_speed = speed;
+        }
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,144 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 51: UART Command Interpreter         *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write as _;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_stm32::mode::Async;
+use embassy_stm32::usart::{Config, Uart, UartTx};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::cli::Command;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, read_line, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+const HELP: &str = "Commands: led on | led off | blink <ms> | help\r\n";
+
+#[derive(Clone, Copy, PartialEq, Eq, Format)]
+enum LedMode {
+    Off,
+    On,
+    Blinking(u32),
+}
+
+static LED_MODE: Signal<CriticalSectionRawMutex, LedMode> = Signal::new();
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    let mut mode = LedMode::Off;
+
+    loop {
+        // The LED keeps running on its own while the main task waits for input
+        mode = match mode {
+            LedMode::Off => {
+                led.set_low();
+                LED_MODE.wait().await
+            }
+            LedMode::On => {
+                led.set_high();
+                LED_MODE.wait().await
+            }
+            LedMode::Blinking(period_ms) => {
+                led.toggle();
+                match select(LED_MODE.wait(), Timer::after_millis(period_ms.into())).await {
+                    Either::First(new_mode) => new_mode,
+                    Either::Second(()) => mode,
+                }
+            }
+        };
+    }
+}
+
+async fn reply(tx: &mut UartTx<'_, Async>, text: &str) {
+    if let Err(e) = tx.write(text.as_bytes()).await {
+        log_uart_error(e);
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+    let (mut tx, mut rx) = usart.split();
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+
+    reply(&mut tx, HELP).await;
+
+    let mut line: String<64> = String::new();
+    let mut response: String<64> = String::new();
+
+    loop {
+        if let Err(e) = read_line(&mut rx, &mut line).await {
+            log_uart_error(e);
+            continue;
+        }
+
+        response.clear();
+        match Command::parse(&line) {
+            Ok(command) => {
+                info!("Command: {}", command);
+                match command {
+                    Command::Led(true) => LED_MODE.signal(LedMode::On),
+                    Command::Led(false) => LED_MODE.signal(LedMode::Off),
+                    Command::Blink(period_ms) => LED_MODE.signal(LedMode::Blinking(period_ms)),
+                    Command::Help => reply(&mut tx, HELP).await,
+                }
+                let _ = response.push_str("OK\r\n");
+            }
+            Err(e) => {
+                warn!("'{}': {}", line.as_str(), e);
+                // The reasons are short, the buffer cannot overflow
+                let _ = write!(response, "ERR: {}\r\n", e.reason());
+            }
+        }
+        reply(&mut tx, &response).await;
+    }
+}
@@ -0,0 +1,95 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 52: Signal Latest Value              *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Pull, Speed};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Blink periods selected in turn by the button, in milliseconds
+const PERIODS_MS: [u32; 4] = [1000, 500, 200, 100];
+
+// Holds at most one value: a new signal replaces one that was not yet taken
+static BLINK_PERIOD_MS: Signal<CriticalSectionRawMutex, u32> = Signal::new();
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+
+    // Nothing to do until the first period is published
+    let mut period_ms = BLINK_PERIOD_MS.wait().await;
+    info!("LED task received {} ms", period_ms);
+
+    loop {
+        led.toggle();
+
+        // Sleep until the next toggle, or wake up at once on a new period
+        match select(BLINK_PERIOD_MS.wait(), Timer::after_millis(period_ms.into())).await {
+            Either::First(new_period_ms) => {
+                period_ms = new_period_ms;
+                info!("LED task received {} ms", period_ms);
+            }
+            Either::Second(()) => {}
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+
+    // The LED task does not run before main awaits, so it misses all but the
+    // last of these: a Signal keeps the latest value, it is not a queue
+    for period_ms in PERIODS_MS {
+        info!("Publishing {} ms", period_ms);
+        BLINK_PERIOD_MS.signal(period_ms);
+    }
+
+    let mut index = PERIODS_MS.len() - 1;
+    loop {
+        button.wait_for_press().await;
+
+        index = (index + 1) % PERIODS_MS.len();
+        info!("Publishing {} ms", PERIODS_MS[index]);
+        BLINK_PERIOD_MS.signal(PERIODS_MS[index]);
+    }
+}
@@ -0,0 +1,68 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 53: Breathing LED                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use getting_started_embassy_stm32f401re::waveform::{gamma, PingPong};
+use {defmt_rtt as _, panic_probe as _};
+
+// Perceptual brightness levels from off to full
+const STEPS: usize = 256;
+// Time for a full fade in and out
+const BREATH_PERIOD_MS: u64 = 4_000;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // The user LED on PA5 (D13) is driven by TIM2_CH1 through the pin's
+    // alternate function, so the pin is handed to the timer, not to an Output
+    let led_pin = PwmPin::new_ch1(board.user_led, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM2, Some(led_pin), None, None, None, khz(1), Default::default());
+    let mut led = pwm.ch1();
+    led.enable();
+
+    let max_duty = led.max_duty_cycle();
+    let curve: [u16; STEPS] = core::array::from_fn(|level| gamma(level as u8, max_duty));
+    let mut stepper = PingPong::new();
+    info!("Max duty {}", max_duty);
+
+    // One breath walks the curve up and down: 2 * (STEPS - 1) steps
+    let step_time = Duration::from_millis(BREATH_PERIOD_MS / (2 * (STEPS as u64 - 1)));
+    let mut ticker = Ticker::every(step_time);
+    loop {
+        led.set_duty_cycle(stepper.next(&curve));
+        ticker.next().await;
+    }
+}
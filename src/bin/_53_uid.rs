@@ -0,0 +1,46 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 54: Unique Device ID                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::uid;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let _p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let id = uid::read();
+    info!("UID words: {=u32:#010x} {=u32:#010x} {=u32:#010x}", id[0], id[1], id[2]);
+    info!("UID: {}", uid::to_hex(id).as_str());
+
+
+    // A short name is easier to read on a label or in a USB device list
+    info!("Serial number: {=u32:#010x}", uid::short_serial(id));
+    info!("Device name: {}", uid::device_name(id).as_str());
+}
@@ -0,0 +1,80 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 55: Reset Cause                      *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use cortex_m::peripheral::SCB;
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::Pull;
+use embassy_stm32::wdg::IndependentWatchdog;
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, PressKind, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::reset::{self, ResetCause};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+const LONG_PRESS: Duration = Duration::from_millis(800);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    // Read the flags before anything else can reset the board again
+    let cause = reset::read_and_clear();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    match cause {
+        ResetCause::IndependentWatchdog | ResetCause::WindowWatchdog => warn!("Reset cause: {}", cause),
+        _ => info!("Reset cause: {}", cause),
+    }
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    info!("Short press: software reset, long press: watchdog reset");
+    info!("The black reset button gives a pin reset, unplugging USB a power-on reset");
+
+    match button.wait_for_press_kind(LONG_PRESS).await {
+        PressKind::Short => {
+            info!("Requesting a software reset");
+            // Let RTT flush the message before the core restarts
+            Timer::after_millis(10).await;
+            SCB::sys_reset();
+        }
+        PressKind::Long => {
+            // Started and never petted: the IWDG resets the board after its timeout
+            let mut wdg = IndependentWatchdog::new(p.IWDG, 1_000_000);
+            wdg.unleash();
+            warn!("Watchdog started without petting, reset in about 1 s");
+            loop {
+                Timer::after_secs(1).await;
+            }
+        }
+    }
+}
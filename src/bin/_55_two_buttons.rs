@@ -0,0 +1,127 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 56: Two Buttons and Chords           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Duration, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Presses of the two buttons closer than this count as a chord
+const CHORD_WINDOW: Duration = Duration::from_millis(150);
+
+#[derive(Clone, Copy, PartialEq, Eq, Format)]
+enum Input {
+    A,
+    B,
+    Both,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Format)]
+enum LedMode {
+    Off,
+    On,
+    Blinking,
+}
+
+static LED_MODE: Signal<CriticalSectionRawMutex, LedMode> = Signal::new();
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    let mut mode = LedMode::Off;
+
+    loop {
+        match mode {
+            LedMode::Off => led.set_low(),
+            LedMode::On => led.set_high(),
+            LedMode::Blinking => led.toggle(),
+        }
+
+        mode = if mode == LedMode::Blinking {
+            match select(LED_MODE.wait(), Timer::after_millis(100)).await {
+                Either::First(new_mode) => new_mode,
+                Either::Second(()) => mode,
+            }
+        } else {
+            LED_MODE.wait().await
+        };
+    }
+}
+
+/// Whether `other` is pressed now or within the chord window.
+async fn joins_in(other: &mut Debouncer<'_>) -> bool {
+    // The press may already be under way: select dropped the other future,
+    // possibly while it was debouncing, and its edge won't come again
+    other.is_pressed() || with_timeout(CHORD_WINDOW, other.wait_for_press()).await.is_ok()
+}
+
+/// Wait for a press of either button, then give the other one a short
+/// window to join in.
+async fn wait_for_input(a: &mut Debouncer<'_>, b: &mut Debouncer<'_>) -> Input {
+    match select(a.wait_for_press(), b.wait_for_press()).await {
+        Either::First(()) if joins_in(b).await => Input::Both,
+        Either::First(()) => Input::A,
+        Either::Second(()) if joins_in(a).await => Input::Both,
+        Either::Second(()) => Input::B,
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Button A: the user button B1 on PC13, which pulls the pin low
+    let button_a = ExtiInput::new(board.user_button, board.button_exti, ActiveLevel::Low.pull());
+    // Button B: push button between PA10 (D2) and 3.3 V, internal pull-down
+    let button_b = ExtiInput::new(p.PA10, p.EXTI10, ActiveLevel::High.pull());
+    let mut a = Debouncer::new(button_a, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    let mut b = Debouncer::new(button_b, ActiveLevel::High, DEFAULT_DEBOUNCE_TIME);
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+    info!("A: LED on, B: LED off, A and B together: blink");
+
+    loop {
+        let input = wait_for_input(&mut a, &mut b).await;
+
+        let mode = match input {
+            Input::A => LedMode::On,
+            Input::B => LedMode::Off,
+            Input::Both => LedMode::Blinking,
+        };
+        info!("{} pressed, LED {}", input, mode);
+        LED_MODE.signal(mode);
+    }
+}
@@ -0,0 +1,76 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 57: UART Read with Timeout           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+// Silence on the line longer than this is reported as idle
+const IDLE_TIMEOUT_SECS: u64 = 5;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+    let (mut tx, mut rx) = usart.split();
+
+    let mut buf = [0u8; 1];
+    loop {
+        // Both futures are created anew on every iteration: the one that
+        // lost the race was dropped at the end of the previous select
+        match select(rx.read(&mut buf), Timer::after_secs(IDLE_TIMEOUT_SECS)).await {
+            Either::First(Ok(())) => {
+                if let Err(e) = tx.write(&buf).await {
+                    log_uart_error(e);
+                }
+            }
+            Either::First(Err(e)) => log_uart_error(e),
+            Either::Second(()) => info!("idle"),
+        }
+    }
+}
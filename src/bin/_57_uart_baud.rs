@@ -0,0 +1,86 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 58: UART Baud Rate and Frame Format  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::usart::{Parity, StopBits, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_config, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+// Set the terminal to the same values: 9600 8E2
+const BAUD_RATE: u32 = 9600;
+const PARITY: Parity = Parity::ParityEven;
+const STOP_BITS: StopBits = StopBits::STOP2;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Start from 8N1 at the chosen baud rate and change what differs
+    let mut config = uart_config(BAUD_RATE);
+    config.parity = PARITY;
+    config.stop_bits = STOP_BITS;
+    info!(
+        "USART2: {} baud, {}, {}, {}",
+        config.baudrate, config.data_bits, config.parity, config.stop_bits
+    );
+
+    let usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        config,
+    ));
+    let (mut tx, mut rx) = usart.split();
+
+    if let Err(e) = tx.write(b"Settings 9600 8E2, type to echo\r\n").await {
+        log_uart_error(e);
+    }
+
+    let mut buf = [0u8; 1];
+    loop {
+        // A terminal with different settings shows up here as framing or parity errors
+        if let Err(e) = rx.read(&mut buf).await {
+            log_uart_error(e);
+            continue;
+        }
+        if let Err(e) = tx.write(&buf).await {
+            log_uart_error(e);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 59: SD Card over SPI                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_stm32::spi::{self, Spi, MODE_0};
+use embassy_stm32::time::{khz, mhz};
+use embassy_time::{Delay, Timer};
+use embedded_hal_bus::spi::ExclusiveDevice;
+use embedded_sdmmc::{SdCard, TimeSource, Timestamp, VolumeIdx, VolumeManager};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+/// The board has no battery-backed clock set up here, so every file
+/// timestamp is the FAT epoch.
+struct FixedTime;
+
+impl TimeSource for FixedTime {
+    fn get_timestamp(&self) -> Timestamp {
+        Timestamp::from_fat(0, 0)
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Cards must be initialized with a clock between 100 and 400 kHz
+    let mut config = spi::Config::default();
+    config.frequency = khz(400);
+    config.mode = MODE_0;
+
+    // SCK PA5 (D13), MISO PA6 (D12), MOSI PA7 (D11), CS PB6 (D10)
+    let mut spi = Spi::new_blocking(p.SPI1, p.PA5, p.PA7, p.PA6, config);
+    let cs = Output::new(p.PB6, Level::High, Speed::VeryHigh);
+
+    // At least 74 clock cycles with CS high switch the card to SPI mode
+    unwrap!(spi.blocking_write(&[0xFF; 10]));
+
+    let device = unwrap!(ExclusiveDevice::new(spi, cs, Delay));
+    let sdcard = SdCard::new(device, Delay);
+
+    // The first access runs CMD0, CMD8 and ACMD41 to initialize the card
+    let size = loop {
+        match sdcard.num_bytes() {
+            Ok(size) => break size,
+            Err(e) => {
+                warn!("No usable SD card ({}), retrying", e);
+                sdcard.mark_card_uninit();
+                Timer::after_secs(1).await;
+            }
+        }
+    };
+    info!("Card size: {} MiB", size / (1024 * 1024));
+    match sdcard.get_card_type() {
+        Some(card_type) => info!("Card type: {}", card_type),
+        None => warn!("Card type unknown"),
+    }
+
+    // Initialized: switch to a fast clock for the data transfers
+    config.frequency = mhz(8);
+    if sdcard.spi(|device| device.bus_mut().set_config(&config)).is_err() {
+        warn!("Could not raise the SPI clock, staying at 400 kHz");
+    }
+
+    let mut volume_mgr = VolumeManager::new(sdcard, FixedTime);
+    let mut volume = match volume_mgr.open_volume(VolumeIdx(0)) {
+        Ok(volume) => volume,
+        Err(e) => {
+            error!("No FAT volume in the first partition: {}", e);
+            return;
+        }
+    };
+    let mut root_dir = match volume.open_root_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            error!("Could not open the root directory: {}", e);
+            return;
+        }
+    };
+
+    info!("Root directory:");
+    let listed = root_dir.iterate_dir(|entry| {
+        if entry.attributes.is_directory() {
+            info!("  {}/", entry.name);
+        } else {
+            info!("  {} ({} bytes)", entry.name, entry.size);
+        }
+    });
+    if let Err(e) = listed {
+        error!("Could not read the root directory: {}", e);
+    }
+}
@@ -0,0 +1,94 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 60: ADC Data Logger                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write as _;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, VrefInt};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use getting_started_embassy_stm32f401re::analog::to_millivolts;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+// Samples per second; at 115200 baud up to about 500 fit on the line
+const SAMPLE_RATE_HZ: u64 = 100;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Signal to log on PA0 (A0), between 0 and 3.3 V
+    let mut adc = Adc::new(p.ADC1);
+    let mut pin = p.PA0;
+
+    // The internal reference makes the millivolts independent of the supply
+    let mut vrefint = adc.enable_vrefint();
+    Timer::after_micros(VrefInt::start_time_us().into()).await;
+    let vrefint_sample = adc.blocking_read(&mut vrefint);
+
+    let mut usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+
+    if let Err(e) = usart.write(b"millis,millivolts\r\n").await {
+        log_uart_error(e);
+    }
+    info!("Logging PA0 at {} Hz", SAMPLE_RATE_HZ);
+
+    let mut line: String<32> = String::new();
+    let mut ticker = Ticker::every(Duration::from_hz(SAMPLE_RATE_HZ));
+    loop {
+        ticker.next().await;
+
+        let timestamp = Instant::now().as_millis();
+        let millivolts = to_millivolts(adc.blocking_read(&mut pin), vrefint_sample);
+
+        line.clear();
+        // A u64 and a u16 take at most 27 characters with the separators
+        let _ = write!(line, "{},{}\r\n", timestamp, millivolts);
+        if let Err(e) = usart.write(line.as_bytes()).await {
+            log_uart_error(e);
+        }
+    }
+}
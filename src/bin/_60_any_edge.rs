@@ -0,0 +1,67 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 61: Any-Edge Interrupt               *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_time::Instant;
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    let mut last_edge = Instant::now();
+    loop {
+        // One await for both directions; the returned level tells which edge it was
+        let level = button.next_edge().await;
+        let elapsed = last_edge.elapsed().as_millis();
+        last_edge = Instant::now();
+
+        // The active level tells whether the edge is a press or a release
+        let pressed = level == button.active_level().pressed();
+        let edge = match level {
+            Level::High => "rising",
+            Level::Low => "falling",
+        };
+        let action = if pressed { "pressed" } else { "released" };
+        info!("{} after {} ms ({})", edge, elapsed, action);
+
+        // Light the LED while the button is held
+        led.set_level(Level::from(pressed));
+    }
+}
@@ -0,0 +1,160 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 62: Blink Period over UART           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write as _;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Pull, Speed};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, read_line, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+const PROMPT: &[u8] = b"Type a blink period in ms (50-5000) and press Enter\r\n";
+
+const MIN_PERIOD_MS: u32 = 50;
+const MAX_PERIOD_MS: u32 = 5000;
+const DEFAULT_PERIOD_MS: u32 = 1000;
+// Each button press shortens the period by this much, wrapping to the maximum
+const BUTTON_STEP_MS: u32 = 300;
+
+// Current period, read by whoever wants to change it relative to its value
+static BLINK_MS: AtomicU32 = AtomicU32::new(DEFAULT_PERIOD_MS);
+// Wakes the LED task with a new period
+static BLINK_CHANGED: Signal<CriticalSectionRawMutex, u32> = Signal::new();
+
+fn set_period(period_ms: u32) {
+    BLINK_MS.store(period_ms, Ordering::Relaxed);
+    BLINK_CHANGED.signal(period_ms);
+}
+
+#[embassy_executor::task]
+async fn led_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    let mut period_ms = BLINK_MS.load(Ordering::Relaxed);
+
+    loop {
+        led.toggle();
+        match select(BLINK_CHANGED.wait(), Timer::after_millis(period_ms.into())).await {
+            Either::First(new_period_ms) => period_ms = new_period_ms,
+            Either::Second(()) => {}
+        }
+    }
+}
+
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    loop {
+        button.wait_for_press().await;
+
+        let period_ms = match BLINK_MS.load(Ordering::Relaxed).saturating_sub(BUTTON_STEP_MS) {
+            p if p < MIN_PERIOD_MS => MAX_PERIOD_MS,
+            p => p,
+        };
+        info!("Button: period {} ms", period_ms);
+        set_period(period_ms);
+    }
+}
+
+/// Parse a blink period typed by the user.
+fn parse_period(line: &str) -> Result<u32, &'static str> {
+    let period_ms: u32 = line.trim().parse().map_err(|_| "not a number")?;
+    if (MIN_PERIOD_MS..=MAX_PERIOD_MS).contains(&period_ms) {
+        Ok(period_ms)
+    } else {
+        Err("out of range, use 50 to 5000 ms")
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    let usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+    let (mut tx, mut rx) = usart.split();
+
+    spawner.spawn(led_task(board.user_led.degrade())).unwrap();
+    spawner.spawn(button_task(button)).unwrap();
+
+    if let Err(e) = tx.write(PROMPT).await {
+        log_uart_error(e);
+    }
+
+    let mut line: String<16> = String::new();
+    let mut reply: String<64> = String::new();
+    loop {
+        if let Err(e) = read_line(&mut rx, &mut line).await {
+            log_uart_error(e);
+            continue;
+        }
+
+        reply.clear();
+        // An invalid line leaves the current period untouched
+        let _ = match parse_period(&line) {
+            Ok(period_ms) => {
+                info!("UART: period {} ms", period_ms);
+                set_period(period_ms);
+                write!(reply, "OK, blinking every {} ms\r\n", period_ms)
+            }
+            Err(reason) => {
+                let current = BLINK_MS.load(Ordering::Relaxed);
+                write!(reply, "ERR: {}, keeping {} ms\r\n", reason, current)
+            }
+        };
+        if let Err(e) = tx.write(reply.as_bytes()).await {
+            log_uart_error(e);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 63: Thermostat with Hysteresis       *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime, Temperature, VrefInt};
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{Duration, Ticker, Timer};
+use getting_started_embassy_stm32f401re::analog::{to_celsius, TemperatureCalibration};
+use getting_started_embassy_stm32f401re::hysteresis::Hysteresis;
+use getting_started_embassy_stm32f401re::logging::{self, init_logging};
+use getting_started_embassy_stm32f401re::{log_info, log_trace, log_warn, take_board};
+use {defmt_rtt as _, panic_probe as _};
+
+// Alarm on above this temperature, in C; a fingertip on the MCU gets there
+const ALARM_ON_C: f32 = 35.0;
+// Alarm off again below this temperature, in C
+const ALARM_OFF_C: f32 = 33.0;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let calibration = TemperatureCalibration::read();
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    let mut adc = Adc::new(p.ADC1);
+    // The temperature sensor needs a sampling time of at least 10 µs
+    adc.set_sample_time(SampleTime::CYCLES480);
+    let mut vrefint = adc.enable_vrefint();
+    let mut temp = adc.enable_temperature();
+    Timer::after_micros(Temperature::start_time_us().max(VrefInt::start_time_us()).into()).await;
+
+    let mut alarm = Hysteresis::new(ALARM_OFF_C, ALARM_ON_C);
+    info!("Alarm on above {} C, off below {} C", ALARM_ON_C, ALARM_OFF_C);
+    info!("Log level {} (log-trace, log-info or log-warn feature)", logging::LEVEL);
+
+    let mut ticker = Ticker::every(Duration::from_millis(500));
+    loop {
+        let vrefint_sample = adc.blocking_read(&mut vrefint);
+        let celsius = to_celsius(adc.blocking_read(&mut temp), vrefint_sample, &calibration);
+        // Every reading: only worth seeing when debugging
+        log_trace!("{} C", celsius);
+
+        let was_on = alarm.is_on();
+        let on = alarm.update(celsius);
+        // Transitions: the alarm is a warning, clearing it is routine
+        if on != was_on {
+            if on {
+                log_warn!("Alarm ON at {} C", celsius);
+            } else {
+                log_info!("Alarm off at {} C", celsius);
+            }
+            led.set_level(Level::from(on));
+        }
+
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,175 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 64: Encoder Menu on OLED             *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_stm32::i2c::I2c;
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::qei::{Qei, QeiPin};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Instant, Timer};
+use embedded_graphics::mono_font::ascii::FONT_6X10;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Baseline, Text};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::encoder::Encoder;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::menu::Menu;
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use ssd1306::prelude::*;
+use ssd1306::{I2CDisplayInterface, Ssd1306};
+use {defmt_rtt as _, panic_probe as _};
+
+const ITEMS: [&str; 6] = ["LED on", "LED off", "LED toggle", "Uptime", "Selections", "About"];
+
+// Quadrature counts per click of a typical encoder (KY-040 and similar)
+const COUNTS_PER_DETENT: i32 = 4;
+// Rows of 12 pixels under the status line
+const ROWS: usize = 4;
+const ROW_HEIGHT: i32 = 12;
+
+static PRESSED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    // Waiting here rather than in the main loop's select: a press that is
+    // being debounced would be lost each time the select drops the future
+    loop {
+        button.wait_for_press().await;
+        PRESSED.signal(());
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Encoder A on PA0 (A0), B on PA1 (A1), as in _21_encoder;
+    // push switch between PA4 (A2) and GND, with the internal pull-up
+    let mut encoder = Encoder::new(Qei::new(p.TIM2, QeiPin::new_ch1(p.PA0), QeiPin::new_ch2(p.PA1)));
+    let button = ExtiInput::new(p.PA4, p.EXTI4, Pull::Up);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    spawner.spawn(button_task(button)).unwrap();
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    // SSD1306 on I2C1, SCL PB8 (D15), SDA PB9 (D14), as in _22_oled_ssd1306
+    let i2c = I2c::new_blocking(p.I2C1, p.PB8, p.PB9, khz(400), Default::default());
+    let interface = I2CDisplayInterface::new(i2c);
+    let mut display =
+        Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0).into_buffered_graphics_mode();
+    while let Err(e) = display.init() {
+        warn!("Display init failed: {}, retrying", Debug2Format(&e));
+        Timer::after_secs(1).await;
+    }
+
+    let normal = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+    let inverted = MonoTextStyle::new(&FONT_6X10, BinaryColor::Off);
+    let highlight = PrimitiveStyle::with_fill(BinaryColor::On);
+
+    let mut menu = Menu::new(&ITEMS, ROWS);
+    let mut status: String<21> = String::new();
+    let _ = status.push_str("Turn and press");
+    let mut selections: u32 = 0;
+    let mut last_detent = encoder.position().div_euclid(COUNTS_PER_DETENT);
+    let mut redraw = true;
+
+    loop {
+        if redraw {
+            display.clear_buffer();
+            // Drawing into the buffer can't fail, only flush talks to the display
+            Text::with_baseline(&status, Point::zero(), normal, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+            for (i, row) in menu.rows().enumerate() {
+                let top_left = Point::new(0, ROW_HEIGHT * (i as i32 + 1) + 4);
+                let style = if row.selected {
+                    Rectangle::new(top_left, Size::new(128, ROW_HEIGHT as u32))
+                        .into_styled(highlight)
+                        .draw(&mut display)
+                        .unwrap();
+                    inverted
+                } else {
+                    normal
+                };
+                Text::with_baseline(row.label, top_left + Point::new(2, 1), style, Baseline::Top)
+                    .draw(&mut display)
+                    .unwrap();
+            }
+            if let Err(e) = display.flush() {
+                warn!("Display update failed: {}", Debug2Format(&e));
+            }
+            redraw = false;
+        }
+
+        match select(PRESSED.wait(), Timer::after_millis(20)).await {
+            Either::First(()) => {
+                selections += 1;
+                status.clear();
+                match menu.selected() {
+                    0 => led.set_high(),
+                    1 => led.set_low(),
+                    2 => led.toggle(),
+                    3 => {
+                        let _ = write!(status, "Up {} s", Instant::now().as_secs());
+                    }
+                    4 => {
+                        let _ = write!(status, "{} selections", selections);
+                    }
+                    _ => {
+                        let _ = status.push_str("Embassy on F401RE");
+                    }
+                }
+                if status.is_empty() {
+                    let _ = status.push_str(menu.selected_label().unwrap_or(""));
+                }
+                info!("Selected {}", menu.selected_label());
+                redraw = true;
+            }
+            Either::Second(()) => {
+                // The encoder counts in hardware, so however far it turned
+                // since the last poll, the whole difference is applied
+                let detent = encoder.position().div_euclid(COUNTS_PER_DETENT);
+                if detent != last_detent {
+                    menu.move_by(detent - last_detent);
+                    last_detent = detent;
+                    redraw = true;
+                }
+            }
+        }
+    }
+}
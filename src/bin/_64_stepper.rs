@@ -0,0 +1,63 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 65: Stepper Motor                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::stepper::{Stepper, STEPS_PER_REVOLUTION};
+use {defmt_rtt as _, panic_probe as _};
+
+// Time per half step; the 28BYJ-48 loses steps below about 1 ms
+const STEP_DELAY: Duration = Duration::from_millis(2);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // ULN2003 inputs IN1 to IN4 on D7, D6, D5, D4
+    let mut stepper = Stepper::new([
+        Output::new(p.PA8, Level::Low, Speed::Low),
+        Output::new(p.PB10, Level::Low, Speed::Low),
+        Output::new(p.PB4, Level::Low, Speed::Low),
+        Output::new(p.PB5, Level::Low, Speed::Low),
+    ]);
+
+    loop {
+        info!("One turn forward");
+        stepper.step(STEPS_PER_REVOLUTION, STEP_DELAY).await;
+
+        info!("Half a turn back");
+        stepper.step(-STEPS_PER_REVOLUTION / 2, STEP_DELAY).await;
+
+        // Unpowered coils don't heat the motor, but the shaft is free to move
+        stepper.release();
+        info!("Position {} half steps, pausing", stepper.position());
+        Timer::after_secs(2).await;
+    }
+}
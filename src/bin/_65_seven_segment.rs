@@ -0,0 +1,85 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 66: 7-Segment Display Multiplexing   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::seg7::{SevenSegment, DIGITS};
+use {defmt_rtt as _, panic_probe as _};
+
+// Each digit is lit for this long: a full scan of 4 digits takes 4 ms (250 Hz)
+const DIGIT_TIME: Duration = Duration::from_millis(1);
+// The counter advances every this many digit refreshes (100 ms)
+const REFRESHES_PER_COUNT: u32 = 100;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let out = |pin: AnyPin, level| Output::new(pin, level, Speed::Low);
+    // Segments a to g and dp on D2 to D9, each through a 1 kOhm resistor
+    let segments = [
+        out(p.PA10.degrade(), Level::Low),
+        out(p.PB3.degrade(), Level::Low),
+        out(p.PB5.degrade(), Level::Low),
+        out(p.PB4.degrade(), Level::Low),
+        out(p.PB10.degrade(), Level::Low),
+        out(p.PA8.degrade(), Level::Low),
+        out(p.PA9.degrade(), Level::Low),
+        out(p.PC7.degrade(), Level::Low),
+    ];
+    // Common cathodes of digits 1 to 4 (left to right) on A0 to A3
+    let digits = [
+        out(p.PA0.degrade(), Level::High),
+        out(p.PA1.degrade(), Level::High),
+        out(p.PA4.degrade(), Level::High),
+        out(p.PB0.degrade(), Level::High),
+    ];
+    let mut display = SevenSegment::new(segments, digits);
+
+    let scan_hz = 1000 / (DIGIT_TIME.as_millis() * DIGITS as u64);
+    info!("Scanning {} digits at {} Hz", DIGITS, scan_hz);
+
+    let mut count: u16 = 0;
+    let mut refreshes: u32 = 0;
+    // A Ticker keeps every digit lit for the same time, so all look equally bright
+    let mut ticker = Ticker::every(DIGIT_TIME);
+    loop {
+        display.refresh();
+
+        refreshes += 1;
+        if refreshes == REFRESHES_PER_COUNT {
+            refreshes = 0;
+            count = (count + 1) % 10_000;
+            display.display_number(count);
+        }
+
+        ticker.next().await;
+    }
+}
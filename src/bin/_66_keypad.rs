@@ -0,0 +1,88 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 67: Matrix Keypad                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{AnyPin, Input, Level, OutputOpenDrain, Pin, Pull, Speed};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use getting_started_embassy_stm32f401re::keypad::Keypad;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Rows on D2 to D5: open drain, so two keys pressed in the same column
+    // can't short a high output against a low one
+    let row = |pin: AnyPin| OutputOpenDrain::new(pin, Level::High, Speed::Low);
+    let rows = [
+        row(p.PA10.degrade()),
+        row(p.PB3.degrade()),
+        row(p.PB5.degrade()),
+        row(p.PB4.degrade()),
+    ];
+    // Columns on D6 to D9, read high unless a key connects them to a low row
+    let col = |pin: AnyPin| Input::new(pin, Pull::Up);
+    let cols = [
+        col(p.PB10.degrade()),
+        col(p.PA8.degrade()),
+        col(p.PA9.degrade()),
+        col(p.PC7.degrade()),
+    ];
+    let mut keypad = Keypad::new(rows, cols);
+
+    let mut usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+    info!("Press a key");
+
+    loop {
+        let key = keypad.next_key().await;
+        info!("Key {}", key);
+
+        let mut utf8 = [0u8; 4];
+        let echo = key.encode_utf8(&mut utf8);
+        if let Err(e) = usart.write(echo.as_bytes()).await {
+            log_uart_error(e);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 68: Servo over UART                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write as _;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, read_line, uart_or_halt};
+use getting_started_embassy_stm32f401re::servo::{Servo, DEFAULT_MAX_US, DEFAULT_MIN_US};
+use getting_started_embassy_stm32f401re::take_board;
+use heapless::String;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Servo signal on PA9 (TIM1_CH2, D8), as in _06_pwm_sg90
+    let ch2_pin = PwmPin::new_ch2(p.PA9, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM1, None, Some(ch2_pin), None, None, hz(50), Default::default());
+    let mut servo = Servo::new(pwm.ch2(), DEFAULT_MIN_US, DEFAULT_MAX_US);
+    servo.set_angle(90.0);
+
+    let usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+    let (mut tx, mut rx) = usart.split();
+
+    if let Err(e) = tx.write(b"Type an angle in degrees (0-180) and press Enter\r\n").await {
+        log_uart_error(e);
+    }
+
+    let mut line: String<16> = String::new();
+    let mut reply: String<48> = String::new();
+    loop {
+        if let Err(e) = read_line(&mut rx, &mut line).await {
+            log_uart_error(e);
+            continue;
+        }
+
+        reply.clear();
+        // The replies are short, the buffer can't overflow
+        let _ = match line.trim().parse::<i32>() {
+            Ok(requested) => {
+                let angle = requested.clamp(0, 180);
+                servo.set_angle(angle as f32);
+                info!("Servo to {} degrees (requested {})", angle, requested);
+                if angle == requested {
+                    write!(reply, "OK {}\r\n", angle)
+                } else {
+                    write!(reply, "OK {} (clamped from {})\r\n", angle, requested)
+                }
+            }
+            // The servo stays where it was
+            Err(_) => write!(reply, "ERR: '{}' is not a whole number\r\n", line.as_str()),
+        };
+        if let Err(e) = tx.write(reply.as_bytes()).await {
+            log_uart_error(e);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 69: Task Supervisor                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::future::pending;
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_stm32::peripherals::IWDG;
+use embassy_stm32::wdg::IndependentWatchdog;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::reset::{self, ResetCause};
+use getting_started_embassy_stm32f401re::supervisor::Supervisor;
+use getting_started_embassy_stm32f401re::take_board;
+use getting_started_embassy_stm32f401re::timing::now_ms;
+use {defmt_rtt as _, panic_probe as _};
+
+const BLINK_TASK: usize = 0;
+const WORKER_TASK: usize = 1;
+const TASK_COUNT: usize = 2;
+const TASK_NAMES: [&str; TASK_COUNT] = ["blink", "worker"];
+
+// Each task checks in well within the window, the window fits inside the watchdog timeout
+const WATCHDOG_TIMEOUT_US: u32 = 2_000_000;
+const CHECK_IN_WINDOW_MS: u32 = 1_000;
+const SUPERVISOR_PERIOD: Duration = Duration::from_millis(250);
+
+static SUPERVISOR: Supervisor<TASK_COUNT> = Supervisor::new();
+static HANG_WORKER: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+#[embassy_executor::task]
+async fn blink_task(mut led: Output<'static>) {
+    loop {
+        led.toggle();
+        SUPERVISOR.check_in(BLINK_TASK, now_ms());
+        Timer::after_millis(500).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn worker_task() {
+    let mut rounds: u32 = 0;
+    loop {
+        SUPERVISOR.check_in(WORKER_TASK, now_ms());
+        if HANG_WORKER.signaled() {
+            warn!("Worker hangs after {} rounds, the other tasks keep running", rounds);
+            // Simulate a bug: the task waits for an event that never comes
+            pending::<()>().await;
+        }
+        rounds = rounds.wrapping_add(1);
+        Timer::after_millis(200).await;
+    }
+}
+
+// Not supervised: it legitimately waits for as long as nobody presses the button
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    button.wait_for_press().await;
+    HANG_WORKER.signal(());
+}
+
+#[embassy_executor::task]
+async fn supervisor_task(mut wdg: IndependentWatchdog<'static, IWDG>) {
+    loop {
+        if let Some(task) = SUPERVISOR.stalled(now_ms(), CHECK_IN_WINDOW_MS) {
+            error!("Task '{}' stalled, no longer petting the watchdog", TASK_NAMES[task]);
+            // Stop petting for good: the board resets once the timeout runs out
+            pending::<()>().await;
+        }
+        wdg.pet();
+        Timer::after(SUPERVISOR_PERIOD).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let cause = reset::read_and_clear();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    match cause {
+        ResetCause::IndependentWatchdog => warn!("Reset cause: {}, a task stalled", cause),
+        _ => info!("Reset cause: {}", cause),
+    }
+
+    let led = Output::new(board.user_led, Level::Low, Speed::Low);
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    let mut wdg = IndependentWatchdog::new(p.IWDG, WATCHDOG_TIMEOUT_US);
+    wdg.unleash();
+
+    unwrap!(spawner.spawn(blink_task(led)));
+    unwrap!(spawner.spawn(worker_task()));
+    unwrap!(spawner.spawn(button_task(button)));
+    unwrap!(spawner.spawn(supervisor_task(wdg)));
+
+    info!("Supervising {} tasks, press B1 to hang the worker", TASK_COUNT);
+}
@@ -0,0 +1,66 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 70: Morse Code Blinker               *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{Duration, Instant, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::morse::{self, Pulses, WORD_GAP_UNITS};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+const MESSAGE: &str = "SOS Nucleo";
+// 12 words per minute gives a 100 ms dot, slow enough to read by eye
+const WORDS_PER_MINUTE: u32 = 12;
+const DOT: Duration = Duration::from_millis(morse::dot_ms(WORDS_PER_MINUTE) as u64);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    info!("Sending \"{}\" with a {} ms dot", MESSAGE, DOT.as_millis());
+
+    // Each pulse ends at an absolute deadline, so the time spent switching
+    // the LED doesn't add up over a long message
+    let mut deadline = Instant::now();
+    loop {
+        for pulse in Pulses::new(MESSAGE) {
+            led.set_level(Level::from(pulse.on));
+            deadline += DOT * pulse.units;
+            Timer::at(deadline).await;
+        }
+
+        led.set_low();
+        info!("Message sent, repeating");
+        deadline += DOT * WORD_GAP_UNITS;
+        Timer::at(deadline).await;
+    }
+}
@@ -0,0 +1,107 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 71: LED Fades with Easing            *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::easing::{interpolate, Easing};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Brightness levels visited in turn, in percent of the maximum duty cycle
+const LEVELS_PERCENT: [u16; 4] = [0, 100, 20, 60];
+// Time for one fade from a level to the next
+const FADE_TIME: Duration = Duration::from_millis(1_500);
+// Pause at each level, so the shape of the fades is easier to see
+const HOLD_TIME: Duration = Duration::from_millis(500);
+const FRAME_TIME: Duration = Duration::from_millis(10);
+
+static NEXT_CURVE: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    loop {
+        button.wait_for_press().await;
+        NEXT_CURVE.signal(());
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let led_pin = PwmPin::new_ch1(board.user_led, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM2, Some(led_pin), None, None, None, khz(1), Default::default());
+    let mut led = pwm.ch1();
+    led.enable();
+    let max_duty = led.max_duty_cycle();
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    unwrap!(spawner.spawn(button_task(button)));
+
+    let level_duty = |index: usize| (max_duty as u32 * LEVELS_PERCENT[index] as u32 / 100) as u16;
+    let mut easing = Easing::Linear;
+    let mut index = 0;
+    info!("Easing {}, press the user button for the next curve", easing);
+
+    loop {
+        // A new curve only takes effect between fades, so no fade jumps halfway
+        if NEXT_CURVE.try_take().is_some() {
+            easing = easing.next();
+            info!("Easing {}", easing);
+        }
+
+        let next = (index + 1) % LEVELS_PERCENT.len();
+        let (from, to) = (level_duty(index), level_duty(next));
+
+        let start = Instant::now();
+        let mut ticker = Ticker::every(FRAME_TIME);
+        loop {
+            let elapsed = start.elapsed();
+            let t = elapsed.as_micros() as f32 / FADE_TIME.as_micros() as f32;
+            led.set_duty_cycle(interpolate(from, to, easing.apply(t)));
+            if elapsed >= FADE_TIME {
+                break;
+            }
+            ticker.next().await;
+        }
+
+        index = next;
+        Timer::after(HOLD_TIME).await;
+    }
+}
@@ -0,0 +1,69 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 72: GPIO Output Speed                *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{Instant, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+const SPEEDS: [Speed; 4] = [Speed::Low, Speed::Medium, Speed::High, Speed::VeryHigh];
+// Enough toggles to last a few hundred milliseconds, long enough to look at on a scope
+const TOGGLES: u32 = 1_000_000;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // PA8 (D7) is free on the Nucleo: connect a scope probe there
+    let mut pin = p.PA8;
+    info!("Toggling PA8 (D7) at every output speed");
+
+    loop {
+        for speed in SPEEDS {
+            // The Output borrows the pin, so it can be recreated with another speed
+            let mut out = Output::new(&mut pin, Level::Low, speed);
+
+            // Blocking on purpose: nothing else may run between the toggles
+            let start = Instant::now();
+            for _ in 0..TOGGLES {
+                out.toggle();
+            }
+            let elapsed_us = start.elapsed().as_micros();
+
+            // Two toggles make one period of the square wave
+            let frequency_khz = TOGGLES as u64 * 1_000 / 2 / elapsed_us.max(1);
+            info!(
+                "{}: {} toggles in {} us, square wave of {} kHz",
+                speed, TOGGLES, elapsed_us, frequency_khz
+            );
+
+            Timer::after_secs(1).await;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 73: Open-Drain Output                *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, OutputOpenDrain, Speed};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Starts released: with the external pull-up the line idles high, LED off
+    let mut line = OutputOpenDrain::new(p.PA8, Level::High, Speed::Low);
+    info!("Open-drain line on PA8 (D7): LED on while driven low");
+
+    loop {
+        for driven in [Level::Low, Level::High] {
+            line.set_level(driven);
+            // Give the pull-up time to charge the line before reading it back
+            Timer::after_micros(10).await;
+
+            // The input buffer stays connected, so the pin reads the real line level
+            let level = line.get_level();
+            match (driven, level) {
+                (Level::Low, _) => info!("Driving low: line reads {}, LED on", level),
+                (Level::High, Level::High) => info!("Released: pull-up holds the line high, LED off"),
+                (Level::High, Level::Low) => warn!("Released, but the line is low: another device pulls it down"),
+            }
+
+            Timer::after_millis(500).await;
+        }
+    }
+}
@@ -0,0 +1,70 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 74: Analog Joystick                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime};
+use embassy_stm32::gpio::{Input, Pull};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::adc_filter::read_averaged;
+use getting_started_embassy_stm32f401re::joystick::Axis;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+// Fraction of the travel around the center that reads as zero
+const DEADZONE: f32 = 0.08;
+// Readings averaged to find the rest position at startup
+const CALIBRATION_SAMPLES: u16 = 64;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC1);
+    // The joystick pots are 10 kΩ: a longer sampling time lets the ADC settle
+    adc.set_sample_time(SampleTime::CYCLES144);
+    let mut x_pin = p.PA0;
+    let mut y_pin = p.PA1;
+
+    // The switch connects SW to GND when pressed: pull-up, active low
+    let button = Input::new(p.PA10, Pull::Up);
+
+    info!("Calibrating, leave the joystick centered");
+    let x_axis = Axis::new(read_averaged(&mut adc, &mut x_pin, CALIBRATION_SAMPLES), DEADZONE);
+    let y_axis = Axis::new(read_averaged(&mut adc, &mut y_pin, CALIBRATION_SAMPLES), DEADZONE);
+    info!("Center X = {}, Y = {}", x_axis.center(), y_axis.center());
+
+    let mut ticker = Ticker::every(Duration::from_millis(200));
+    loop {
+        let x = x_axis.normalize(adc.blocking_read(&mut x_pin));
+        let y = y_axis.normalize(adc.blocking_read(&mut y_pin));
+        let button_state = if button.is_low() { "pressed" } else { "released" };
+        info!("X = {}, Y = {}, button {}", x, y, button_state);
+
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,104 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 75: Motor Soft Start                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::motor::HBridgeMotor;
+use getting_started_embassy_stm32f401re::ramp::Ramp;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Running speed, in percent
+const TARGET_SPEED: f32 = 80.0;
+// Time to go from standstill to full speed, and back
+const RAMP_TIME: Duration = Duration::from_millis(2_000);
+const TICK: Duration = Duration::from_millis(20);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    loop {
+        button.wait_for_press().await;
+        RUNNING.fetch_xor(true, Ordering::Relaxed);
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Same wiring as _05_pwm_motor: IN1 on PB6 (TIM4_CH1, D10), IN2 on PA7 (TIM3_CH2, D11)
+    let in1_pin = PwmPin::new_ch1(p.PB6, OutputType::PushPull);
+    let in2_pin = PwmPin::new_ch2(p.PA7, OutputType::PushPull);
+    let mut pwm_in1 = SimplePwm::new(p.TIM4, Some(in1_pin), None, None, None, hz(100), Default::default());
+    let mut pwm_in2 = SimplePwm::new(p.TIM3, None, Some(in2_pin), None, None, hz(100), Default::default());
+    let mut motor = HBridgeMotor::new(pwm_in1.ch1(), pwm_in2.ch2());
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    unwrap!(spawner.spawn(button_task(button)));
+
+    // Full scale (100%) in RAMP_TIME, one step per tick
+    let steps = (RAMP_TIME.as_millis() / TICK.as_millis()) as f32;
+    let mut ramp = Ramp::new(100.0 / steps);
+    info!("Press the user button to start and stop the motor");
+
+    let mut ticker = Ticker::every(TICK);
+    loop {
+        let target = if RUNNING.load(Ordering::Relaxed) {
+            TARGET_SPEED
+        } else {
+            0.0
+        };
+        if target != ramp.target() {
+            info!("Ramping from {}% to {}%", ramp.value(), target);
+            ramp.set_target(target);
+        }
+
+        if !ramp.is_settled() {
+            let speed = ramp.step();
+            motor.set_speed(speed as i8);
+            if ramp.is_settled() {
+                info!("Reached {}%", speed);
+            }
+        }
+
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,94 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 76: Frequency Meter                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+// Counting time: with a 1 s gate the count is directly the frequency in Hz
+const GATE: Duration = Duration::from_secs(1);
+// Gates measured at each test frequency
+const GATES_PER_FREQUENCY: u32 = 3;
+// Test signal frequencies, from easy to beyond what EXTI can count
+const TEST_FREQUENCIES_HZ: [u32; 5] = [10, 1_000, 10_000, 50_000, 100_000];
+
+static EDGES: AtomicU32 = AtomicU32::new(0);
+
+#[embassy_executor::task]
+async fn counter_task(mut input: ExtiInput<'static>) {
+    loop {
+        input.wait_for_rising_edge().await;
+        EDGES.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Test signal on PB4 (TIM3_CH1, D5), same as _20_input_capture.
+    // Connect D5 to D7 to measure it, or feed D7 from an external source.
+    let test_pin = PwmPin::new_ch1(p.PB4, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(p.TIM3, Some(test_pin), None, None, None, hz(10), Default::default());
+
+    // Signal under test on PA8 (D7), EXTI line 8
+    let input = ExtiInput::new(p.PA8, p.EXTI8, Pull::None);
+    unwrap!(spawner.spawn(counter_task(input)));
+
+    loop {
+        for frequency in TEST_FREQUENCIES_HZ {
+            pwm.set_frequency(hz(frequency));
+            let mut test_signal = pwm.ch1();
+            test_signal.set_duty_cycle_percent(50);
+            test_signal.enable();
+            info!("Test signal at {} Hz", frequency);
+
+            // The gate runs on its own schedule, the counter task never waits for it
+            let mut ticker = Ticker::every(GATE);
+            // Discard the edges counted while the frequency was being changed
+            EDGES.store(0, Ordering::Relaxed);
+            for _ in 0..GATES_PER_FREQUENCY {
+                ticker.next().await;
+                let count = EDGES.swap(0, Ordering::Relaxed);
+                let measured_hz = (count as u64 * 1_000_000 / GATE.as_micros()) as u32;
+                if measured_hz + measured_hz / 100 < frequency {
+                    warn!("Measured {} Hz: edges lost, too fast for EXTI counting", measured_hz);
+                } else {
+                    info!("Measured {} Hz", measured_hz);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 77: Pull-Up and Pull-Down            *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::select;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Input, Pull};
+use embassy_time::{Duration, Ticker, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+const PULLS: [Pull; 3] = [Pull::None, Pull::Up, Pull::Down];
+// Readings per report, spread over 100 ms
+const SAMPLES: u32 = 100;
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(1);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+
+    // Leave PA10 (D2) unconnected, or touch it with a finger or a wire
+    let mut pin = p.PA10;
+    info!("Reading PA10 (D2), press the user button for the next pull setting");
+
+    for pull in PULLS.iter().cycle() {
+        // The Input borrows the pin, so it can be recreated with another pull
+        let input = Input::new(&mut pin, *pull);
+        info!("Pull::{}", pull);
+
+        let sample = async {
+            loop {
+                let mut highs = 0;
+                let mut ticker = Ticker::every(SAMPLE_INTERVAL);
+                for _ in 0..SAMPLES {
+                    if input.is_high() {
+                        highs += 1;
+                    }
+                    ticker.next().await;
+                }
+                match highs {
+                    0 => info!("  low"),
+                    SAMPLES => info!("  high"),
+                    _ => warn!("  undefined: {} of {} readings high", highs, SAMPLES),
+                }
+                Timer::after_millis(400).await;
+            }
+        };
+
+        // Sampling never ends, so this returns when the button is pressed
+        select(sample, button.wait_for_press()).await;
+    }
+}
@@ -0,0 +1,121 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 78: Critical Sections                *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use defmt::*;
+use embassy_executor::{InterruptExecutor, Spawner};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Pull};
+use embassy_stm32::interrupt;
+use embassy_stm32::interrupt::{InterruptExt, Priority};
+use embassy_time::{Duration, Instant, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+/// Button statistics, updated on every edge.
+///
+/// The fields only make sense together: `presses` and `releases` never
+/// differ by more than one, and `last_edge` belongs to the latest of them.
+/// An atomic per field could not keep that, a reader might see a new
+/// `presses` with an old `releases`.
+#[derive(Clone, Copy)]
+struct ButtonStats {
+    presses: u32,
+    releases: u32,
+    last_edge: Option<Instant>,
+}
+
+static STATS: Mutex<RefCell<ButtonStats>> = Mutex::new(RefCell::new(ButtonStats {
+    presses: 0,
+    releases: 0,
+    last_edge: None,
+}));
+
+// Runs its tasks inside the USART6 interrupt, which no example uses, so they
+// preempt everything in the main executor
+static EXECUTOR_HIGH: InterruptExecutor = InterruptExecutor::new();
+
+#[interrupt]
+unsafe fn USART6() {
+    EXECUTOR_HIGH.on_interrupt()
+}
+
+#[embassy_executor::task]
+async fn edge_task(mut button: ExtiInput<'static>) {
+    loop {
+        button.wait_for_any_edge().await;
+        let level = button.get_level();
+
+        // Interrupt context: the borrow can't overlap with a reader, since
+        // the reader also holds the critical section while it borrows
+        critical_section::with(|cs| {
+            let mut stats = STATS.borrow_ref_mut(cs);
+            // B1 is active-low: the falling edge is the press
+            match level {
+                Level::Low => stats.presses += 1,
+                Level::High => stats.releases += 1,
+            }
+            stats.last_edge = Some(Instant::now());
+        });
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // No debouncing on purpose: bounces show up as extra edges. B1 has an
+    // external pull-up on the board and pulls PC13 low while pressed.
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+
+    interrupt::USART6.set_priority(Priority::P6);
+    let high_priority = EXECUTOR_HIGH.start(interrupt::USART6);
+    unwrap!(high_priority.spawn(edge_task(button)));
+
+    let mut ticker = Ticker::every(Duration::from_secs(1));
+    loop {
+        ticker.next().await;
+
+        // Copy the whole struct out in one critical section, then log the
+        // copy with interrupts enabled again
+        let stats = critical_section::with(|cs| *STATS.borrow_ref(cs));
+
+        match stats.last_edge {
+            Some(last_edge) => info!(
+                "{} presses, {} releases, last edge {} ms ago",
+                stats.presses,
+                stats.releases,
+                last_edge.elapsed().as_millis()
+            ),
+            None => info!("No edges yet, press the user button"),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 79: Config in Flash                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::flash::Flash;
+use embassy_stm32::gpio::Pull;
+use embassy_time::Duration;
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, PressKind, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::config_store::{Config, FlashConfigStore, SLOTS};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+const LONG_PRESS: Duration = Duration::from_millis(800);
+
+/// Step the servo end point through 2000..=2500 us.
+fn next_servo_max(us: u16) -> u16 {
+    if us >= 2_500 {
+        2_000
+    } else {
+        us + 50
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // Scanning only reads: a blank or corrupted sector is not an error
+    let mut store = unwrap!(FlashConfigStore::open(Flash::new_blocking(p.FLASH)));
+
+    let mut config = store.config();
+    match store.latest() {
+        Some(record) => info!("Loaded record #{}: {}", record.sequence, config),
+        None => warn!("No valid config in flash, using defaults: {}", config),
+    }
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    info!("Short press: change the servo end point and save, long press: save the defaults");
+
+    loop {
+        match button.wait_for_press_kind(LONG_PRESS).await {
+            // Stand-in for a real calibration step
+            PressKind::Short => config.servo_max_us = next_servo_max(config.servo_max_us),
+            PressKind::Long => config = Config::default(),
+        }
+
+        match store.save(&config) {
+            Ok(slot) => info!("Saved to slot {} of {}: {}", slot, SLOTS, config),
+            Err(e) => error!("Flash write failed: {}", e),
+        }
+    }
+}
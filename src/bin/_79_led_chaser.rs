@@ -0,0 +1,76 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 80: LED Chaser                       *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::chaser::Chaser;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use heapless::Vec;
+use {defmt_rtt as _, panic_probe as _};
+
+const MAX_LEDS: usize = 8;
+// Time the light stays on each LED: lower is faster
+const STEP_TIME: Duration = Duration::from_millis(80);
+// The LED just left stays on this long, a short tail behind the light
+const TRAIL_TIME: Duration = Duration::from_millis(30);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // D2 to D9 on the Arduino header, in order: each pin drives an LED
+    // through a 330 Ω resistor to GND
+    let pins: [AnyPin; MAX_LEDS] = [
+        p.PA10.degrade(),
+        p.PB3.degrade(),
+        p.PB5.degrade(),
+        p.PB4.degrade(),
+        p.PB10.degrade(),
+        p.PA8.degrade(),
+        p.PA9.degrade(),
+        p.PC7.degrade(),
+    ];
+    // Degraded pins all have the same type, so they fit in one collection
+    let mut leds: Vec<Output<'static>, MAX_LEDS> = pins
+        .into_iter()
+        .map(|pin| Output::new(pin, Level::Low, Speed::Low))
+        .collect();
+
+    let mut chaser = Chaser::new(leds.len());
+    leds[chaser.position()].set_high();
+    info!("{} LEDs, {} ms per step", leds.len(), STEP_TIME.as_millis());
+
+    loop {
+        let position = chaser.step();
+        leds[position].set_high();
+        Timer::after(TRAIL_TIME).await;
+        leds[chaser.previous()].set_low();
+        Timer::after(STEP_TIME - TRAIL_TIME).await;
+    }
+}
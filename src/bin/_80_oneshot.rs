@@ -0,0 +1,90 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 81: Retriggerable One-Shot           *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Instant, Timer};
+use getting_started_embassy_stm32f401re::button::{ActiveLevel, Debouncer, DEFAULT_DEBOUNCE_TIME};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// The LED goes off this long after the last press
+const ON_TIME: Duration = Duration::from_secs(2);
+
+static PRESSED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+// Debouncing runs in its own task, so a press can't be lost when the
+// timeout wins the select below
+#[embassy_executor::task]
+async fn button_task(mut button: Debouncer<'static>) {
+    loop {
+        button.wait_for_press().await;
+        PRESSED.signal(());
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let button = Debouncer::new(button, ActiveLevel::Low, DEFAULT_DEBOUNCE_TIME);
+    unwrap!(spawner.spawn(button_task(button)));
+
+    info!("Press the button, the LED stays on {} ms", ON_TIME.as_millis());
+
+    loop {
+        // Idle: nothing to time out, just wait for the trigger
+        PRESSED.wait().await;
+        led.set_high();
+        info!("On");
+
+        // Active: each press pushes the deadline back
+        let mut deadline = Instant::now() + ON_TIME;
+        loop {
+            match select(PRESSED.wait(), Timer::at(deadline)).await {
+                Either::First(()) => {
+                    deadline = Instant::now() + ON_TIME;
+                    info!("Retriggered, off in {} ms", ON_TIME.as_millis());
+                }
+                Either::Second(()) => break,
+            }
+        }
+
+        led.set_low();
+        info!("Off");
+    }
+}
@@ -0,0 +1,84 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 82: Bit-Banged I2C                   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, OutputOpenDrain, Pull, Speed};
+use embassy_stm32::time::{khz, mhz};
+use embassy_time::Timer;
+use embedded_hal_async::i2c::I2c;
+use getting_started_embassy_stm32f401re::hts221::{self, Hts221};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::soft_i2c::{Error, SoftI2c};
+use {defmt_rtt as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    // Default config: the core runs from the 16 MHz HSI
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // SCL on PA9 (D8), SDA on PC7 (D9): neither pin can be routed to an I2C
+    // peripheral. External 4.7 kΩ pull-ups to 3V3 are recommended, the
+    // internal ones are enabled as a fallback for short wires.
+    let scl = OutputOpenDrain::new_pull(p.PA9, Level::High, Speed::Low, Pull::Up);
+    let sda = OutputOpenDrain::new_pull(p.PC7, Level::High, Speed::Low, Pull::Up);
+    let mut i2c = SoftI2c::new(scl, sda, mhz(16), khz(100));
+
+    info!("Scanning the bit-banged bus...");
+    let mut found = 0;
+    let mut buf = [0u8; 1];
+
+    // 0x00..0x07 and 0x78..0x7F are reserved addresses
+    for addr in 0x08..=0x77u8 {
+        match i2c.read(addr, &mut buf).await {
+            Ok(()) => {
+                info!("Found device at {=u8:#04x}", addr);
+                found += 1;
+            }
+            // Nobody at this address, keep scanning
+            Err(Error::AddressNack) => {}
+            Err(e) => warn!("Error at {=u8:#04x}: {}", addr, e),
+        }
+    }
+    info!("Scan complete: {} device(s) found", found);
+
+    // The same drivers as with the hardware I2C work on the soft bus
+    let mut sensor = match Hts221::new(&mut i2c).await {
+        Ok(sensor) => sensor,
+        Err(e) => {
+            info!("No HTS221 at {=u8:#04x} ({}), done", hts221::ADDRESS, e);
+            return;
+        }
+    };
+    loop {
+        match sensor.read().await {
+            Ok(m) => info!("HTS221: {} C, {} %RH", m.temperature, m.humidity),
+            Err(e) => warn!("HTS221 read failed: {}", e),
+        }
+        Timer::after_secs(1).await;
+    }
+}
@@ -0,0 +1,82 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 83: Complementary PWM with Dead-Time *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::peripherals::TIM1;
+use embassy_stm32::rcc;
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::complementary_pwm::{ComplementaryPwm, ComplementaryPwmPin};
+use embassy_stm32::timer::low_level::CountingMode;
+use embassy_stm32::timer::simple_pwm::PwmPin;
+use embassy_stm32::timer::Channel;
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use {defmt_rtt as _, panic_probe as _};
+
+// Time both outputs are held low around every switching edge
+const DEAD_TIME_NS: u32 = 1_000;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // TIM1_CH1 drives the high side, TIM1_CH1N its complement for the low side
+    let high_side = PwmPin::new_ch1(p.PA8, OutputType::PushPull);
+    let low_side = ComplementaryPwmPin::new_ch1(p.PA7, OutputType::PushPull);
+    let mut pwm = ComplementaryPwm::new(
+        p.TIM1,
+        Some(high_side),
+        Some(low_side),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        khz(20),
+        CountingMode::EdgeAlignedUp,
+    );
+
+    // The dead-time generator counts timer clock ticks, so convert from nanoseconds
+    let timer_clock = rcc::frequency::<TIM1>();
+    let dead_time_ticks = (timer_clock.0 as u64 * DEAD_TIME_NS as u64 / 1_000_000_000) as u16;
+    pwm.set_dead_time(dead_time_ticks);
+
+    let max_duty = pwm.get_max_duty();
+    pwm.set_duty(Channel::Ch1, max_duty / 2);
+    pwm.enable(Channel::Ch1);
+
+    info!("Timer clock: {} Hz, PWM: 20 kHz, max duty: {}", timer_clock.0, max_duty);
+    info!("Dead time: {} ns = {} ticks", DEAD_TIME_NS, dead_time_ticks);
+    info!("CH1 on PA8 (D7), CH1N on PA7 (D11), duty 50%");
+
+    loop {
+        Timer::after_secs(1).await;
+    }
+}
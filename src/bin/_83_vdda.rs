@@ -0,0 +1,61 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 84: Supply Voltage from VrefInt      *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, VrefInt};
+use embassy_time::Timer;
+use getting_started_embassy_stm32f401re::analog::{read_vdda_mv, read_vrefint_cal, VREFINT_MV};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::timing::delay_us;
+use {defmt_rtt as _, panic_probe as _};
+
+// Full scale of the 12-bit ADC
+const ADC_MAX: u32 = 4095;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC1);
+    let mut vrefint = adc.enable_vrefint();
+    delay_us(VrefInt::start_time_us());
+
+    info!("VREFINT_CAL: {} (sample at VDDA = 3300 mV)", read_vrefint_cal());
+
+    loop {
+        let vdda_mv = read_vdda_mv(&mut adc, &mut vrefint);
+
+        // The same measurement with the typical datasheet VREFINT, for comparison
+        let sample = u32::from(adc.blocking_read(&mut vrefint));
+        let typical_mv = VREFINT_MV * ADC_MAX / sample;
+
+        info!("VDDA: {} mV (typical VREFINT gives {} mV)", vdda_mv, typical_mv);
+
+        Timer::after_secs(1).await;
+    }
+}
@@ -0,0 +1,102 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 85: Battery Monitor                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, VrefInt};
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{Duration, Ticker, Timer};
+use getting_started_embassy_stm32f401re::adc_filter::read_averaged;
+use getting_started_embassy_stm32f401re::analog::read_vdda_mv;
+use getting_started_embassy_stm32f401re::battery::{sample_to_millivolts, Divider, LowBatteryAlarm};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use getting_started_embassy_stm32f401re::timing::delay_us;
+use {defmt_rtt as _, panic_probe as _};
+
+// Two equal resistors halve the battery voltage, enough for one Li-ion cell
+const DIVIDER: Divider = Divider::new(10_000, 10_000);
+// The alarm goes on below ALARM_BELOW_MV and clears above CLEAR_ABOVE_MV
+const ALARM_BELOW_MV: u32 = 3_500;
+const CLEAR_ABOVE_MV: u32 = 3_700;
+const SAMPLES: u16 = 16;
+const MEASURE_INTERVAL: Duration = Duration::from_secs(1);
+const BLINK_TIME: Duration = Duration::from_millis(100);
+
+static BATTERY_LOW: AtomicBool = AtomicBool::new(false);
+
+#[embassy_executor::task]
+async fn warning_task(mut led: Output<'static>) {
+    loop {
+        if BATTERY_LOW.load(Ordering::Relaxed) {
+            led.toggle();
+        } else {
+            led.set_low();
+        }
+        Timer::after(BLINK_TIME).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let led = Output::new(board.user_led, Level::Low, Speed::Low);
+    unwrap!(spawner.spawn(warning_task(led)));
+
+    let mut adc = Adc::new(p.ADC1);
+    let mut battery_pin = p.PA0;
+    let mut vrefint = adc.enable_vrefint();
+    delay_us(VrefInt::start_time_us());
+
+    let mut alarm = LowBatteryAlarm::new(ALARM_BELOW_MV, CLEAR_ABOVE_MV);
+    let mut ticker = Ticker::every(MEASURE_INTERVAL);
+
+    loop {
+        // The supply is measured every time, since it can drift too
+        let vdda_mv = read_vdda_mv(&mut adc, &mut vrefint);
+        let sample = read_averaged(&mut adc, &mut battery_pin, SAMPLES);
+        let battery_mv = DIVIDER.battery_millivolts(sample_to_millivolts(sample, vdda_mv));
+
+        let was_low = alarm.is_low();
+        let low = alarm.update(battery_mv);
+        BATTERY_LOW.store(low, Ordering::Relaxed);
+
+        if low && !was_low {
+            warn!("Battery low: {} mV", battery_mv);
+        } else if !low && was_low {
+            info!("Battery recovered: {} mV", battery_mv);
+        } else {
+            info!("Battery: {} mV (VDDA {} mV)", battery_mv, vdda_mv);
+        }
+
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,111 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 86: Periodic Jobs from One Task      *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::scheduler::PeriodicJob;
+use getting_started_embassy_stm32f401re::take_board;
+use getting_started_embassy_stm32f401re::timing::now_ms;
+use {defmt_rtt as _, panic_probe as _};
+
+// The loop wakes up at this rate; job periods should be multiples of it
+const TICK: Duration = Duration::from_millis(10);
+
+/// Statistics of the ADC samples since the last report.
+struct Stats {
+    count: u32,
+    sum: u32,
+    min: u16,
+    max: u16,
+}
+
+impl Stats {
+    const fn new() -> Self {
+        Self {
+            count: 0,
+            sum: 0,
+            min: u16::MAX,
+            max: 0,
+        }
+    }
+
+    fn add(&mut self, sample: u16) {
+        self.count += 1;
+        self.sum += u32::from(sample);
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+    }
+}
+
+// With one task per job, each would need its own stack-allocated future,
+// and `Stats` would have to be shared through a Mutex or a channel. Here
+// the jobs run one after the other in the same loop, so they share plain
+// local variables. The price: a slow job delays the others, and a job that
+// blocks stops them all. Tasks remain the better choice for jobs that wait
+// on events, such as a button or incoming UART data.
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+    let mut adc = Adc::new(p.ADC1);
+    let mut pot = p.PA0;
+    let mut stats = Stats::new();
+
+    // Blinking at 1 Hz takes a toggle every 500 ms
+    let mut blink = PeriodicJob::new(500);
+    let mut sample = PeriodicJob::new(100);
+    let mut report = PeriodicJob::new(5_000);
+
+    let mut ticker = Ticker::every(TICK);
+    loop {
+        let now = now_ms();
+
+        if blink.should_run(now) {
+            led.toggle();
+        }
+
+        // Reported before sampling, so a report covers the samples of the
+        // last 5 s and not the one taken at the same tick
+        if report.should_run(now) && stats.count > 0 {
+            let Stats { count, sum, min, max } = stats;
+            info!("{} samples: min {}, mean {}, max {}", count, min, sum / count, max);
+            stats = Stats::new();
+        }
+
+        if sample.should_run(now) {
+            stats.add(adc.blocking_read(&mut pot));
+        }
+
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,139 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 87: RC Servo Passthrough             *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{OutputType, Pull};
+use embassy_stm32::peripherals::TIM2;
+use embassy_stm32::time::{hz, Hertz};
+use embassy_stm32::timer::input_capture::{CapturePin, InputCapture};
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_stm32::timer::{self, Channel};
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_time::{with_timeout, Duration, Instant, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::servo::{Servo, DEFAULT_MAX_US, DEFAULT_MIN_US};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    TIM1_CC => timer::CaptureCompareInterruptHandler<peripherals::TIM1>;
+});
+
+// One capture tick per microsecond, so tick differences are pulse widths
+const TICK_FREQ: Hertz = Hertz(1_000_000);
+// RC receivers repeat the pulse every 20 ms; five missing frames count as lost
+const PULSE_TIMEOUT: Duration = Duration::from_millis(100);
+// After this long without a valid pulse, the output is centered
+const FAILSAFE_TIME: Duration = Duration::from_secs(1);
+const CENTER_US: u32 = (DEFAULT_MIN_US + DEFAULT_MAX_US) / 2;
+// Pulses outside this range are glitches, not RC commands
+const VALID_US: core::ops::RangeInclusive<u32> = 800..=2_200;
+
+#[embassy_executor::task]
+async fn test_signal_task(mut signal: Servo<'static, TIM2>) {
+    // Sweep 1000 -> 2000 us in 10 us steps, then back, like a stick moved slowly
+    loop {
+        for pulse_us in (DEFAULT_MIN_US..DEFAULT_MAX_US)
+            .step_by(10)
+            .chain((DEFAULT_MIN_US + 10..=DEFAULT_MAX_US).rev().step_by(10))
+        {
+            signal.set_pulse_us(pulse_us);
+            Timer::after_millis(20).await;
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Test RC signal on PA0 (TIM2_CH1, A0). Connect A0 to D7 to use it, or
+    // feed D7 from an RC receiver channel instead.
+    let test_pin = PwmPin::new_ch1(p.PA0, OutputType::PushPull);
+    let test_pwm = SimplePwm::new(p.TIM2, Some(test_pin), None, None, None, hz(50), Default::default());
+    let test_signal = Servo::new(test_pwm.split().ch1, DEFAULT_MIN_US, DEFAULT_MAX_US);
+    unwrap!(spawner.spawn(test_signal_task(test_signal)));
+
+    // RC input on PA8 (TIM1_CH1, D7)
+    let capture_pin = CapturePin::new_ch1(p.PA8, Pull::None);
+    let mut capture = InputCapture::new(
+        p.TIM1,
+        Some(capture_pin),
+        None,
+        None,
+        None,
+        Irqs,
+        TICK_FREQ,
+        Default::default(),
+    );
+
+    // Servo output on PB4 (TIM3_CH1, D5)
+    let servo_pin = PwmPin::new_ch1(p.PB4, OutputType::PushPull);
+    let mut servo_pwm = SimplePwm::new(p.TIM3, Some(servo_pin), None, None, None, hz(50), Default::default());
+    let mut servo = Servo::new(servo_pwm.ch1(), DEFAULT_MIN_US, DEFAULT_MAX_US);
+    servo.set_pulse_us(CENTER_US);
+
+    let mut last_valid = Instant::now();
+    let mut failsafe = false;
+    let mut logged_us = 0;
+
+    loop {
+        let pulse = with_timeout(PULSE_TIMEOUT, async {
+            let rise = capture.wait_for_rising_edge(Channel::Ch1).await as u16;
+            let fall = capture.wait_for_falling_edge(Channel::Ch1).await as u16;
+            // Wrapping difference stays correct across a counter overflow
+            u32::from(fall.wrapping_sub(rise))
+        })
+        .await;
+
+        match pulse {
+            Ok(pulse_us) if VALID_US.contains(&pulse_us) => {
+                // The servo clamps the pulse to its own 1000-2000 us range
+                servo.set_pulse_us(pulse_us);
+                last_valid = Instant::now();
+                if failsafe {
+                    failsafe = false;
+                    info!("Input back: {} us", pulse_us);
+                }
+                // Log only noticeable changes, not every 20 ms frame
+                if pulse_us.abs_diff(logged_us) >= 50 {
+                    info!("Pulse {} us", pulse_us);
+                    logged_us = pulse_us;
+                }
+            }
+            Ok(pulse_us) => warn!("Ignoring {} us pulse", pulse_us),
+            // Until the failsafe time has passed, the servo holds its last position
+            Err(_) => {}
+        }
+
+        if !failsafe && last_valid.elapsed() >= FAILSAFE_TIME {
+            failsafe = true;
+            servo.set_pulse_us(CENTER_US);
+            warn!("Input lost, centering the servo");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 88: Binary Telemetry with postcard   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, Temperature, VrefInt};
+use embassy_stm32::gpio::{Input, Pull};
+use embassy_stm32::usart::{Config, Uart};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use embassy_time::{Duration, Instant, Ticker};
+use getting_started_embassy_stm32f401re::analog::{to_celsius, to_millivolts, TemperatureCalibration};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use getting_started_embassy_stm32f401re::telemetry::{Telemetry, MAX_FRAME_SIZE};
+use getting_started_embassy_stm32f401re::timing::delay_us;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::InterruptHandler<peripherals::USART2>;
+});
+
+const SAMPLE_RATE_HZ: u64 = 10;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let calibration = TemperatureCalibration::read();
+    let mut adc = Adc::new(p.ADC1);
+    let mut pin = p.PA0;
+    let mut vrefint = adc.enable_vrefint();
+    let mut temperature = adc.enable_temperature();
+    delay_us(Temperature::start_time_us().max(VrefInt::start_time_us()));
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = Input::new(board.user_button, Pull::None);
+
+    let mut usart = uart_or_halt(Uart::new(
+        board.vcp_usart,
+        board.vcp_rx,
+        board.vcp_tx,
+        Irqs,
+        board.vcp_tx_dma,
+        board.vcp_rx_dma,
+        Config::default(),
+    ));
+
+    info!("Sending telemetry frames at {} Hz", SAMPLE_RATE_HZ);
+
+    let mut buf = [0u8; MAX_FRAME_SIZE];
+    let mut ticker = Ticker::every(Duration::from_hz(SAMPLE_RATE_HZ));
+    loop {
+        ticker.next().await;
+
+        let vrefint_sample = adc.blocking_read(&mut vrefint);
+        let telemetry = Telemetry {
+            timestamp_ms: Instant::now().as_millis() as u32,
+            adc_mv: to_millivolts(adc.blocking_read(&mut pin), vrefint_sample),
+            temperature_c: to_celsius(adc.blocking_read(&mut temperature), vrefint_sample, &calibration),
+            button_pressed: button.is_low(),
+        };
+
+        match telemetry.encode(&mut buf) {
+            Ok(frame) => {
+                debug!("{} -> {} bytes", telemetry, frame.len());
+                if let Err(e) = usart.write(frame).await {
+                    log_uart_error(e);
+                }
+            }
+            Err(e) => warn!("Encoding failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 89: Broadcast with PubSubChannel     *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::{PubSubChannel, Subscriber, WaitResult};
+use embassy_time::{Duration, Instant, Ticker, Timer};
+use getting_started_embassy_stm32f401re::hysteresis::Hysteresis;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+/// One potentiometer reading, as broadcast to every subscriber.
+#[derive(Clone, Copy, Format)]
+struct Reading {
+    timestamp_ms: u64,
+    raw: u16,
+}
+
+// Messages kept for the slowest subscriber, number of subscribers, number of publishers
+const CAPACITY: usize = 4;
+const SUBSCRIBERS: usize = 2;
+const PUBLISHERS: usize = 1;
+
+static READINGS: PubSubChannel<CriticalSectionRawMutex, Reading, CAPACITY, SUBSCRIBERS, PUBLISHERS> =
+    PubSubChannel::new();
+
+type ReadingSubscriber = Subscriber<'static, CriticalSectionRawMutex, Reading, CAPACITY, SUBSCRIBERS, PUBLISHERS>;
+
+const SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+// The logger takes longer per message than the sensor needs per reading,
+// like a logger writing to a slow UART, so it falls behind
+const LOG_TIME: Duration = Duration::from_millis(150);
+// LED on above 60% of the ADC range, off again below 40%
+const LED_ON_ABOVE: f32 = 2_457.0;
+const LED_OFF_BELOW: f32 = 1_638.0;
+
+#[embassy_executor::task]
+async fn logger_task(mut readings: ReadingSubscriber) {
+    loop {
+        match readings.next_message().await {
+            WaitResult::Message(reading) => {
+                info!("{} ms: {}", reading.timestamp_ms, reading.raw);
+                Timer::after(LOG_TIME).await;
+            }
+            // The oldest messages were overwritten before this task read them
+            WaitResult::Lagged(missed) => warn!("Logger lagged, {} readings missed", missed),
+        }
+    }
+}
+
+#[embassy_executor::task]
+async fn threshold_task(mut readings: ReadingSubscriber, led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    let mut threshold = Hysteresis::new(LED_OFF_BELOW, LED_ON_ABOVE);
+
+    loop {
+        // Only the latest level matters for the LED, so lag is not an error here
+        let reading = readings.next_message_pure().await;
+        let on = threshold.update(f32::from(reading.raw));
+        if on != led.is_set_high() {
+            led.set_level(Level::from(on));
+            info!("LED {}", if on { "on" } else { "off" });
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC1);
+    let mut pot = p.PA0;
+
+    // Each subscriber gets every message; asking for more than SUBSCRIBERS fails
+    unwrap!(spawner.spawn(logger_task(unwrap!(READINGS.subscriber()))));
+    unwrap!(spawner.spawn(threshold_task(unwrap!(READINGS.subscriber()), board.user_led.degrade())));
+
+    let publisher = unwrap!(READINGS.publisher());
+    let mut ticker = Ticker::every(SAMPLE_PERIOD);
+    loop {
+        let reading = Reading {
+            timestamp_ms: Instant::now().as_millis(),
+            raw: adc.blocking_read(&mut pot),
+        };
+        // Never wait for slow subscribers: the oldest reading is overwritten instead
+        publisher.publish_immediate(reading);
+        ticker.next().await;
+    }
+}
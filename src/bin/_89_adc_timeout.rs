@@ -0,0 +1,85 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 90: ADC Reads with a Timeout         *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime, Sequence};
+use embassy_stm32::gpio::{Input, Pull};
+use embassy_time::{Duration, Timer};
+use getting_started_embassy_stm32f401re::adc_filter::{read_with_timeout, ReadError};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+// Conversions per read, alternating between PA0 and PA1
+const WINDOW: usize = 64;
+const DMA_BUF_LEN: usize = WINDOW * 2;
+// At 480 + 12 ADC cycles of 125 ns, a window takes about 3.9 ms
+const TIMEOUT: Duration = Duration::from_millis(20);
+// Far too short for a window: used while the user button is held
+const TIGHT_TIMEOUT: Duration = Duration::from_millis(1);
+
+// Mean of one channel in a window where the two channels alternate
+fn channel_mean(samples: &[u16], channel: usize) -> u32 {
+    let sum: u32 = samples.iter().skip(channel).step_by(2).map(|&s| u32::from(s)).sum();
+    sum / (samples.len() as u32 / 2)
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let mut p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let button = Input::new(board.user_button, Pull::None);
+
+    let mut adc_dma_buf = [0u16; DMA_BUF_LEN];
+    let mut adc = Adc::new(p.ADC1).into_ring_buffered(p.DMA2_CH0, &mut adc_dma_buf);
+    adc.set_sample_sequence(Sequence::One, &mut p.PA0, SampleTime::CYCLES480);
+    adc.set_sample_sequence(Sequence::Two, &mut p.PA1, SampleTime::CYCLES480);
+
+    let mut samples = [0u16; WINDOW];
+    info!("Hold the user button to simulate a conversion that takes too long");
+
+    loop {
+        let timeout = if button.is_low() { TIGHT_TIMEOUT } else { TIMEOUT };
+
+        match read_with_timeout(timeout, adc.read(&mut samples)).await {
+            Ok(_) => {
+                info!("PA0 {}, PA1 {}", channel_mean(&samples, 0), channel_mean(&samples, 1));
+            }
+            // Already logged by read_with_timeout
+            Err(ReadError::Timeout) => {}
+            Err(ReadError::Failed(e)) => warn!("ADC read failed: {}", e),
+        }
+
+        // Stop converting until the next read. After a timeout this also
+        // abandons the half-filled window: the next read starts afresh.
+        adc.teardown_adc();
+        Timer::after_millis(500).await;
+    }
+}
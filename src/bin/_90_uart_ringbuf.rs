@@ -0,0 +1,100 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 91: UART RX Ring Buffer              *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::usart::{BufferedUart, Config};
+use embassy_stm32::{bind_interrupts, peripherals, usart};
+use embassy_time::{Duration, Timer};
+use embedded_io_async::{Read, Write};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::serial::{log_uart_error, uart_or_halt};
+use getting_started_embassy_stm32f401re::take_board;
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    USART2 => usart::BufferedInterruptHandler<peripherals::USART2>;
+});
+
+// At 115200 baud about 11.5 bytes arrive per millisecond, so 256 bytes
+// cover about 22 ms in which the main loop does not read
+const RX_BUF_SIZE: usize = 256;
+const TX_BUF_SIZE: usize = 256;
+// Time the main loop spends on something else after each chunk
+const PROCESSING_TIME: Duration = Duration::from_millis(10);
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    // The interrupt handler writes into these for the whole program run
+    static RX_BUFFER: StaticCell<[u8; RX_BUF_SIZE]> = StaticCell::new();
+    static TX_BUFFER: StaticCell<[u8; TX_BUF_SIZE]> = StaticCell::new();
+    let mut usart = uart_or_halt(BufferedUart::new(
+        board.vcp_usart,
+        Irqs,
+        board.vcp_rx,
+        board.vcp_tx,
+        TX_BUFFER.init([0; TX_BUF_SIZE]),
+        RX_BUFFER.init([0; RX_BUF_SIZE]),
+        Config::default(),
+    ));
+
+    if let Err(e) = usart.write_all(b"Type or paste some text\r\n").await {
+        log_uart_error(e);
+    }
+
+    let mut chunk = [0u8; 64];
+    let mut total: u32 = 0;
+    let mut largest = 0;
+
+    loop {
+        // Returns what the interrupt has buffered so far, at least one byte
+        let len = match usart.read(&mut chunk).await {
+            Ok(len) => len,
+            Err(e) => {
+                log_uart_error(e);
+                continue;
+            }
+        };
+
+        total += len as u32;
+        if len > largest {
+            largest = len;
+            info!("Largest chunk so far: {} bytes ({} received)", largest, total);
+        }
+
+        if let Err(e) = usart.write_all(&chunk[..len]).await {
+            log_uart_error(e);
+        }
+
+        // Bytes that arrive meanwhile are stored by the interrupt handler
+        Timer::after(PROCESSING_TIME).await;
+    }
+}
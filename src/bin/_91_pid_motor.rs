@@ -0,0 +1,116 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 92: PID Motor Speed Control          *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::qei::{Qei, QeiPin};
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::adc_filter::{read_averaged, Ema};
+use getting_started_embassy_stm32f401re::control::Pid;
+use getting_started_embassy_stm32f401re::encoder::Encoder;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::motor::HBridgeMotor;
+use {defmt_rtt as _, panic_probe as _};
+
+// Full scale of the 12-bit ADC
+const ADC_MAX: f32 = 4095.0;
+// Speed setpoint with the pot at its maximum, in encoder counts per second.
+// Keep it below the speed the motor reaches at 100% duty.
+const MAX_SPEED: f32 = 2000.0;
+// Setpoints below this stop the motor instead of regulating a crawl
+const MIN_SPEED: f32 = 100.0;
+
+// Control loop period
+const LOOP_PERIOD: Duration = Duration::from_millis(10);
+const DT: f32 = LOOP_PERIOD.as_micros() as f32 / 1_000_000.0;
+// Log every this many loop iterations (5 times per second)
+const LOG_EVERY: u32 = 20;
+
+// Gains: output in percent duty per count/s of error (see _91_pid_motor.md)
+const KP: f32 = 0.02;
+const KI: f32 = 0.2;
+const KD: f32 = 0.0;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // Encoder A on PA0 (TIM2_CH1, A0), B on PA1 (TIM2_CH2, A1), as in _21_encoder
+    let ch_a = QeiPin::new_ch1(p.PA0);
+    let ch_b = QeiPin::new_ch2(p.PA1);
+    let mut encoder = Encoder::new(Qei::new(p.TIM2, ch_a, ch_b));
+
+    // Potentiometer wiper on PA4 (A2), since A0 is taken by the encoder
+    let mut adc = Adc::new(p.ADC1);
+    let mut pot = p.PA4;
+    let mut ema = Ema::new(0.3);
+
+    // Same wiring as _49_pot_motor: IN1 on PB6 (TIM4_CH1, D10), IN2 on PA7 (TIM3_CH2, D11)
+    let in1_pin = PwmPin::new_ch1(p.PB6, OutputType::PushPull);
+    let in2_pin = PwmPin::new_ch2(p.PA7, OutputType::PushPull);
+    let mut pwm_in1 = SimplePwm::new(p.TIM4, Some(in1_pin), None, None, None, hz(100), Default::default());
+    let mut pwm_in2 = SimplePwm::new(p.TIM3, None, Some(in2_pin), None, None, hz(100), Default::default());
+    let mut motor = HBridgeMotor::new(pwm_in1.ch1(), pwm_in2.ch2());
+
+    // Forward only: the output is a duty cycle from 0 to 100%
+    let mut pid = Pid::new(KP, KI, KD, 0.0, 100.0);
+
+    let mut last_position = encoder.position();
+    let mut iteration = 0u32;
+    let mut ticker = Ticker::every(LOOP_PERIOD);
+    loop {
+        let sample = ema.update(read_averaged(&mut adc, &mut pot, 8));
+        let setpoint = f32::from(sample) / ADC_MAX * MAX_SPEED;
+
+        let position = encoder.position();
+        let measured = position.wrapping_sub(last_position) as f32 / DT;
+        last_position = position;
+
+        let output = if setpoint < MIN_SPEED {
+            // Stopped: let the motor coast and start the next run from a
+            // clean controller
+            pid.reset();
+            0.0
+        } else {
+            pid.update(setpoint, measured, DT)
+        };
+        motor.set_speed(output as i8);
+
+        iteration = iteration.wrapping_add(1);
+        if iteration % LOG_EVERY == 0 {
+            info!(
+                "setpoint {} counts/s, measured {} counts/s, duty {}%",
+                setpoint as i32, measured as i32, output as i32
+            );
+        }
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,91 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 93: Button with WFI                  *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+use cortex_m_rt::entry;
+use defmt::*;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Output, Pull, Speed};
+use embassy_stm32::Config;
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+/// Run `future` to completion, sleeping with WFI while it is pending.
+///
+/// A minimal version of what the embassy executor does between tasks.
+fn sleep_until<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    // Nobody needs to be woken: after every interrupt the future is simply
+    // polled again
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        // With interrupts disabled, an interrupt that arrives between the
+        // poll and WFI stays pending and ends WFI at once instead of being
+        // missed. Its handler runs as soon as `free` enables them again.
+        let output = cortex_m::interrupt::free(|_| match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => Some(output),
+            Poll::Pending => {
+                cortex_m::asm::wfi();
+                None
+            }
+        });
+        if let Some(output) = output {
+            return output;
+        }
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    init_logging();
+
+    info!("Hello World!");
+
+    let mut config = Config::default();
+    // Otherwise the debug interface keeps the core clock running during
+    // sleep. RTT logging still works, as the bus clocks keep running.
+    config.enable_debug_during_sleep = false;
+    let p = embassy_stm32::init(config);
+    let board = take_board!(p);
+
+    // B1 has an external pull-up on the board and pulls PC13 low while pressed
+    let mut button = ExtiInput::new(board.user_button, board.button_exti, Pull::None);
+    let mut led1 = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    loop {
+        // Each wait returns at once if the button is already at that level,
+        // so a change between two waits is never missed
+        sleep_until(button.wait_for_low());
+        info!("pressed");
+        led1.set_high();
+
+        sleep_until(button.wait_for_high());
+        info!("unpressed");
+        led1.set_low();
+    }
+}
@@ -0,0 +1,99 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 94: Four LEDs on One PWM Timer       *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::OutputType;
+use embassy_stm32::time::khz;
+use embassy_stm32::timer::simple_pwm::{PwmPin, SimplePwm};
+use embassy_stm32::timer::Channel;
+use embassy_time::{Duration, Ticker, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::waveform::gamma;
+use {defmt_rtt as _, panic_probe as _};
+
+// The four compare channels of the timer, in LED order
+const CHANNELS: [Channel; 4] = [Channel::Ch1, Channel::Ch2, Channel::Ch3, Channel::Ch4];
+// Fixed duty cycles shown first, one per LED
+const DUTY_PERCENT: [u8; 4] = [5, 25, 50, 100];
+// Brightness wave: each LED fades in and out over PERIOD_STEPS updates, a
+// quarter period after its neighbor
+const UPDATE_PERIOD: Duration = Duration::from_millis(10);
+const PERIOD_STEPS: u32 = 200;
+
+/// Triangle wave from 0 up to 255 and back over `PERIOD_STEPS` steps.
+fn triangle(step: u32) -> u8 {
+    let phase = step % PERIOD_STEPS;
+    let distance = phase.min(PERIOD_STEPS - phase);
+    (distance * 2 * 255 / PERIOD_STEPS) as u8
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // One LED per TIM3 channel, each through a 220 Ohm resistor to GND:
+    // CH1 on PB4 (D5), CH2 on PC7 (D9), CH3 on PB0 (A3), CH4 on PB1 (CN10 pin 24)
+    let ch1 = PwmPin::new_ch1(p.PB4, OutputType::PushPull);
+    let ch2 = PwmPin::new_ch2(p.PC7, OutputType::PushPull);
+    let ch3 = PwmPin::new_ch3(p.PB0, OutputType::PushPull);
+    let ch4 = PwmPin::new_ch4(p.PB1, OutputType::PushPull);
+    // All four channels share the counter of TIM3, and with it this frequency
+    let mut pwm = SimplePwm::new(
+        p.TIM3,
+        Some(ch1),
+        Some(ch2),
+        Some(ch3),
+        Some(ch4),
+        khz(1),
+        Default::default(),
+    );
+    let max_duty = pwm.max_duty_cycle();
+    info!("PWM at 1 kHz, max duty {}", max_duty);
+
+    // Each channel has its own compare register, so its duty cycle is
+    // independent of the others
+    for (channel, percent) in CHANNELS.into_iter().zip(DUTY_PERCENT) {
+        let mut ch = pwm.channel(channel);
+        ch.set_duty_cycle_percent(percent);
+        ch.enable();
+    }
+    info!("Fixed duty cycles {}%", DUTY_PERCENT);
+    Timer::after_secs(3).await;
+
+    info!("Brightness wave");
+    let mut step = 0u32;
+    let mut ticker = Ticker::every(UPDATE_PERIOD);
+    loop {
+        for (led, channel) in CHANNELS.into_iter().enumerate() {
+            let level = triangle(step + led as u32 * PERIOD_STEPS / 4);
+            pwm.channel(channel).set_duty_cycle(gamma(level, max_duty));
+        }
+        step = (step + 1) % PERIOD_STEPS;
+        ticker.next().await;
+    }
+}
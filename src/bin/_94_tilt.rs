@@ -0,0 +1,143 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 95: Tilt Angles from the IMU         *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{Level, Output, Speed};
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::time::khz;
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_time::{Duration, Ticker};
+use getting_started_embassy_stm32f401re::control::{tilt_from_acceleration, ComplementaryFilter};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::lsm6dsl::{self, Error, FullScale, GyroFullScale, Lsm6dsl, OutputDataRate};
+use getting_started_embassy_stm32f401re::take_board;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+// Filter update rate; the sensor runs at 104 Hz, a bit faster
+const UPDATE_PERIOD: Duration = Duration::from_millis(10);
+const DT: f32 = 0.01;
+// Crossover of the complementary filter: movements faster than this come
+// from the gyroscope, slower ones from the accelerometer
+const TIME_CONSTANT: f32 = 0.5;
+// Samples averaged at startup to measure the gyroscope offset
+const CALIBRATION_SAMPLES: u32 = 100;
+// The LED lights while both angles are within this many degrees of level
+const LEVEL_TOLERANCE: f32 = 2.0;
+// Log every this many updates (5 times per second)
+const LOG_EVERY: u32 = 20;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    let mut led = Output::new(board.user_led, Level::Low, Speed::Low);
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14)
+    let i2c = I2c::new(
+        p.I2C1,
+        p.PB8,
+        p.PB9,
+        Irqs,
+        p.DMA1_CH7,
+        p.DMA1_CH0,
+        khz(100),
+        Default::default(),
+    );
+
+    let address = lsm6dsl::ADDRESS_SA0_HIGH;
+    let mut imu = match Lsm6dsl::new(i2c, address, OutputDataRate::Hz104, FullScale::G2).await {
+        Ok(imu) => imu,
+        Err(Error::WrongDevice(id)) => {
+            error!("WHO_AM_I returned {=u8:#04x}, expected 0x6a: is this an LSM6DSL?", id);
+            return;
+        }
+        Err(Error::I2c(e)) => {
+            error!("LSM6DSL not responding: {}", e);
+            return;
+        }
+    };
+    // +/-245 degrees/s is plenty for tilting a board by hand
+    let gyro_range = GyroFullScale::Dps245;
+    if let Err(e) = imu.configure_gyroscope(OutputDataRate::Hz104, gyro_range).await {
+        error!("Gyroscope configuration failed: {}", e);
+        return;
+    }
+    info!("LSM6DSL found");
+
+    // A gyroscope at rest rarely reads exactly zero. The filter corrects the
+    // drift this causes, but the offset still shifts the angles slightly.
+    info!("Keep the board still...");
+    let mut ticker = Ticker::every(UPDATE_PERIOD);
+    let (mut offset_x, mut offset_y) = (0.0, 0.0);
+    for _ in 0..CALIBRATION_SAMPLES {
+        ticker.next().await;
+        let rate = unwrap!(imu.read_angular_rate().await);
+        offset_x += rate.x;
+        offset_y += rate.y;
+    }
+    offset_x /= CALIBRATION_SAMPLES as f32;
+    offset_y /= CALIBRATION_SAMPLES as f32;
+    info!("Gyroscope offset X: {} dps, Y: {} dps", offset_x, offset_y);
+
+    let mut pitch_filter = ComplementaryFilter::new(TIME_CONSTANT);
+    let mut roll_filter = ComplementaryFilter::new(TIME_CONSTANT);
+    let mut iteration = 0u32;
+    loop {
+        ticker.next().await;
+        let (a, rate) = match (imu.read_acceleration().await, imu.read_angular_rate().await) {
+            (Ok(a), Ok(rate)) => (a, rate),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Read failed: {}", e);
+                continue;
+            }
+        };
+
+        // Pitch turns around Y and roll around X, so each angle pairs with
+        // the rate around the same axis
+        let tilt = tilt_from_acceleration(a.x, a.y, a.z);
+        let pitch = pitch_filter.update(tilt.pitch, rate.y - offset_y, DT);
+        let roll = roll_filter.update(tilt.roll, rate.x - offset_x, DT);
+
+        // A spirit level: the LED lights when the board lies flat
+        led.set_level((pitch.abs() < LEVEL_TOLERANCE && roll.abs() < LEVEL_TOLERANCE).into());
+
+        iteration = iteration.wrapping_add(1);
+        if iteration % LOG_EVERY == 0 {
+            info!(
+                "pitch {} deg, roll {} deg (accelerometer only: {}, {})",
+                pitch, roll, tilt.pitch, tilt.roll
+            );
+        }
+    }
+}
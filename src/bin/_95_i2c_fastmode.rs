@@ -0,0 +1,122 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 96: I2C Fast Mode                    *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::mode::Async;
+use embassy_stm32::time::{khz, Hertz};
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_time::{Duration, Instant};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::lsm6dsl::{self, Error, FullScale, Lsm6dsl, OutputDataRate};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+// Reads timed at each bus speed
+const TRANSACTIONS: u32 = 1000;
+// Bytes on the wire per read: address + register, then address + 6 data bytes
+const BYTES_PER_TRANSACTION: u32 = 9;
+
+/// Read the accelerometer `TRANSACTIONS` times and return how long it took.
+async fn time_reads(i2c: &mut I2c<'_, Async>) -> Result<Duration, Error<i2c::Error>> {
+    // Fastest data rate, so every read gets fresh data; the timing doesn't
+    // depend on it, as the reads don't wait for new samples
+    let address = lsm6dsl::ADDRESS_SA0_HIGH;
+    let mut imu = Lsm6dsl::new(&mut *i2c, address, OutputDataRate::Hz1660, FullScale::G2).await?;
+    let start = Instant::now();
+    for _ in 0..TRANSACTIONS {
+        imu.read_raw().await?;
+    }
+    Ok(start.elapsed())
+}
+
+/// Log the result of the reads at `frequency`; the elapsed time in
+/// microseconds, or `None` if the sensor didn't answer.
+fn report(frequency: Hertz, result: Result<Duration, Error<i2c::Error>>) -> Option<u64> {
+    match result {
+        Ok(elapsed) => {
+            let us = elapsed.as_micros();
+            let bytes_per_s = u64::from(TRANSACTIONS * BYTES_PER_TRANSACTION) * 1_000_000 / us;
+            info!(
+                "{} kHz: {} reads in {} ms, {} us per read, {} bytes/s",
+                frequency.0 / 1000,
+                TRANSACTIONS,
+                us / 1000,
+                us / u64::from(TRANSACTIONS),
+                bytes_per_s
+            );
+            Some(us)
+        }
+        Err(e) => {
+            error!("{} kHz: LSM6DSL read failed: {}", frequency.0 / 1000, e);
+            None
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14).
+    // The drivers below only borrow these, so each speed can get its own.
+    let mut i2c1 = p.I2C1;
+    let mut scl = p.PB8;
+    let mut sda = p.PB9;
+    let mut tx_dma = p.DMA1_CH7;
+    let mut rx_dma = p.DMA1_CH0;
+
+    // Standard mode first, like the scanner, then fast mode
+    let mut results = [None; 2];
+    for (result, frequency) in results.iter_mut().zip([khz(100), khz(400)]) {
+        // The clock registers may only be written while the peripheral is
+        // disabled, so each speed gets a new driver, which configures the
+        // peripheral from reset; the previous one is dropped at the end of
+        // the iteration
+        let mut i2c = I2c::new(
+            &mut i2c1,
+            &mut scl,
+            &mut sda,
+            Irqs,
+            &mut tx_dma,
+            &mut rx_dma,
+            frequency,
+            Default::default(),
+        );
+        *result = report(frequency, time_reads(&mut i2c).await);
+    }
+    if let [Some(standard), Some(fast)] = results {
+        // Two decimals without floating point
+        let speedup = standard * 100 / fast;
+        info!("Fast mode is {}.{=u64:02}x faster", speedup / 100, speedup % 100);
+    }
+}
@@ -0,0 +1,111 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 97: I2C Bus Recovery                 *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::mode::Async;
+use embassy_stm32::time::khz;
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_time::{Duration, Ticker, Timer};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::lsm6dsl::{self, Error, FullScale, Lsm6dsl, OutputDataRate};
+use getting_started_embassy_stm32f401re::recovery::recover_i2c_bus;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+// Time between retries while the bus can't be freed
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Read the accelerometer until a transfer fails, and return the error.
+async fn read_until_error(i2c: &mut I2c<'_, Async>) -> Error<i2c::Error> {
+    let address = lsm6dsl::ADDRESS_SA0_HIGH;
+    let mut imu = match Lsm6dsl::new(&mut *i2c, address, OutputDataRate::Hz52, FullScale::G2).await {
+        Ok(imu) => imu,
+        Err(e) => return e,
+    };
+    info!("LSM6DSL ready");
+
+    let mut ticker = Ticker::every(Duration::from_millis(200));
+    loop {
+        ticker.next().await;
+        match imu.read_acceleration().await {
+            Ok(a) => info!("X: {} g, Y: {} g, Z: {} g", a.x, a.y, a.z),
+            Err(e) => return e,
+        }
+    }
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    init_logging();
+
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    // I2C1 on the Arduino header: PB8 is SCL (D15), PB9 is SDA (D14).
+    // The driver only borrows its peripherals, so they can be handed to the
+    // recovery code and to a new driver after a fault.
+    let mut i2c1 = p.I2C1;
+    let mut scl = p.PB8;
+    let mut sda = p.PB9;
+    let mut tx_dma = p.DMA1_CH7;
+    let mut rx_dma = p.DMA1_CH0;
+
+    loop {
+        // Also runs at startup: a reset in the middle of a transfer leaves
+        // the sensor holding the bus, since it keeps its power
+        match recover_i2c_bus(&mut scl, &mut sda) {
+            Ok(0) => {}
+            Ok(pulses) => warn!("Bus freed after {} clock pulses", pulses),
+            Err(e) => {
+                error!("Bus recovery failed: {}", e);
+                Timer::after(RETRY_DELAY).await;
+                continue;
+            }
+        }
+
+        // Creating the driver resets the I2C peripheral, clearing any error
+        // state it was left in
+        let mut i2c = I2c::new(
+            &mut i2c1,
+            &mut scl,
+            &mut sda,
+            Irqs,
+            &mut tx_dma,
+            &mut rx_dma,
+            khz(100),
+            Default::default(),
+        );
+        let error = read_until_error(&mut i2c).await;
+        warn!("I2C failed: {}, recovering the bus", error);
+        // Dropping the driver releases the pins for the recovery
+        drop(i2c);
+        Timer::after(RETRY_DELAY).await;
+    }
+}
@@ -0,0 +1,85 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/****************************************************
+*            RAPID PROTOTYPING WITH NUCLEO          *
+* Example Code 98: Delay vs Sleep                   *
+* Organization: Perlatecnica APS ETS                *
+*****************************************************/
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::gpio::{AnyPin, Level, Output, Pin, Speed};
+use getting_started_embassy_stm32f401re::logging::init_logging;
+use getting_started_embassy_stm32f401re::take_board;
+use getting_started_embassy_stm32f401re::timing::{delay_ms, sleep_ms};
+use {defmt_rtt as _, panic_probe as _};
+
+// Core clock out of reset, and with the default configuration: the 16 MHz HSI
+const CORE_HZ: u32 = 16_000_000;
+// Settling time of external hardware after power-up, before it is touched
+const POWER_UP_MS: u32 = 50;
+const BLINK_MS: u32 = 50;
+const WAIT_MS: u32 = 500;
+
+// LED toggles so far, counted by the blink task
+static TOGGLES: AtomicU32 = AtomicU32::new(0);
+
+#[embassy_executor::task]
+async fn blink_task(led: AnyPin) {
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+    loop {
+        led.toggle();
+        TOGGLES.fetch_add(1, Ordering::Relaxed);
+        sleep_ms(BLINK_MS).await;
+    }
+}
+
+/// LED toggles while `wait` runs.
+async fn toggles_during(wait: impl core::future::Future<Output = ()>) -> u32 {
+    let before = TOGGLES.load(Ordering::Relaxed);
+    wait.await;
+    TOGGLES.load(Ordering::Relaxed).wrapping_sub(before)
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    init_logging();
+
+    // The embassy time driver only starts in init, so a wait before it must
+    // count CPU cycles. SysTick is free: the time driver runs on a TIM timer.
+    let cp = unwrap!(cortex_m::Peripherals::take());
+    let mut delay = cortex_m::delay::Delay::new(cp.SYST, CORE_HZ);
+    delay.delay_ms(POWER_UP_MS);
+
+    let p = embassy_stm32::init(Default::default());
+    let board = take_board!(p);
+    info!("Hello World!");
+
+    unwrap!(spawner.spawn(blink_task(board.user_led.degrade())));
+
+    loop {
+        // A sleep yields: the blink task keeps running
+        let sleeping = toggles_during(sleep_ms(WAIT_MS)).await;
+        // A delay spins: nothing else on this executor runs until it ends
+        let delaying = toggles_during(async { delay_ms(WAIT_MS) }).await;
+        info!("LED toggles: {} while sleeping, {} while delaying", sleeping, delaying);
+    }
+}
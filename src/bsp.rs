@@ -0,0 +1,77 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Board support for the NucleoF401RE.
+//!
+//! Maps the on-board devices (user LED, user button, ST-LINK virtual COM port)
+//! to the MCU peripherals they are wired to.
+
+use embassy_stm32::peripherals::{DMA1_CH5, DMA1_CH6, EXTI13, PA2, PA3, PA5, PC13, USART2};
+
+/// On-board devices of the NucleoF401RE.
+///
+/// Created with [`take_board!`](crate::take_board), which leaves every other
+/// peripheral available. The fields keep their concrete peripheral types, so
+/// they can still be degraded (`board.user_led.degrade()`) or routed to an
+/// alternate function.
+pub struct NucleoF401RE {
+    /// Green user LED LD2 on PA5 (Arduino D13).
+    pub user_led: PA5,
+    /// Blue user button B1 on PC13.
+    pub user_button: PC13,
+    /// EXTI line of the user button.
+    pub button_exti: EXTI13,
+    /// USART connected to the ST-LINK virtual COM port.
+    pub vcp_usart: USART2,
+    /// Virtual COM port TX (PA2, Arduino D1).
+    pub vcp_tx: PA2,
+    /// Virtual COM port RX (PA3, Arduino D0).
+    pub vcp_rx: PA3,
+    /// DMA channel for USART2 TX.
+    pub vcp_tx_dma: DMA1_CH6,
+    /// DMA channel for USART2 RX.
+    pub vcp_rx_dma: DMA1_CH5,
+}
+
+/// Move the on-board devices out of the `Peripherals` binding `p`.
+///
+/// Returns a [`NucleoF401RE`](crate::bsp::NucleoF401RE). The on-board
+/// peripherals are moved out field by field, so the other fields of `p`
+/// stay usable afterwards:
+///
+/// ```ignore
+/// let p = embassy_stm32::init(Default::default());
+/// let board = take_board!(p);
+/// let mut led = Output::new(board.user_led, Level::High, Speed::Low);
+/// let mut adc = Adc::new(p.ADC1);
+/// ```
+///
+/// A function could only take the whole struct, and everything it doesn't
+/// return would be lost; this is why it is a macro.
+#[macro_export]
+macro_rules! take_board {
+    ($p:ident) => {
+        $crate::bsp::NucleoF401RE {
+            user_led: $p.PA5,
+            user_button: $p.PC13,
+            button_exti: $p.EXTI13,
+            vcp_usart: $p.USART2,
+            vcp_tx: $p.PA2,
+            vcp_rx: $p.PA3,
+            vcp_tx_dma: $p.DMA1_CH6,
+            vcp_rx_dma: $p.DMA1_CH5,
+        }
+    };
+}
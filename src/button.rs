@@ -0,0 +1,188 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Push-button helpers.
+
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::{Level, Pull};
+use embassy_time::{with_timeout, Duration, Instant, Timer};
+
+/// Debounce window suitable for most tactile switches.
+pub const DEFAULT_DEBOUNCE_TIME: Duration = Duration::from_millis(20);
+
+/// How long a button was held.
+#[derive(Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum PressKind {
+    /// Released before the long-press threshold.
+    Short,
+    /// Held for at least the long-press threshold.
+    Long,
+}
+
+/// Pin level of a button while it is pressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ActiveLevel {
+    /// Pressing connects the pin to ground, like the NucleoF401RE user button.
+    Low,
+    /// Pressing connects the pin to the supply.
+    High,
+}
+
+impl ActiveLevel {
+    /// Pin level while the button is pressed.
+    pub fn pressed(self) -> Level {
+        match self {
+            ActiveLevel::Low => Level::Low,
+            ActiveLevel::High => Level::High,
+        }
+    }
+
+    /// Pin level while the button is released.
+    pub fn released(self) -> Level {
+        match self {
+            ActiveLevel::Low => Level::High,
+            ActiveLevel::High => Level::Low,
+        }
+    }
+
+    /// Pull resistor that holds the pin at the released level.
+    ///
+    /// A button without an external resistor needs it; with one, an internal
+    /// pull in the same direction does no harm.
+    pub fn pull(self) -> Pull {
+        match self {
+            ActiveLevel::Low => Pull::Up,
+            ActiveLevel::High => Pull::Down,
+        }
+    }
+}
+
+/// Debounced push button, wired either active low or active high.
+pub struct Debouncer<'d> {
+    input: ExtiInput<'d>,
+    active: ActiveLevel,
+    debounce_time: Duration,
+    // Level last reported by next_edge
+    level: Level,
+}
+
+impl<'d> Debouncer<'d> {
+    /// Create a debouncer around `input`, pressed when the pin is at `active`.
+    ///
+    /// Create `input` with `active.pull()` unless the board has its own pull
+    /// resistor. An edge is accepted only if the pin still holds the new
+    /// level `debounce_time` after the edge.
+    pub fn new(input: ExtiInput<'d>, active: ActiveLevel, debounce_time: Duration) -> Self {
+        let level = input.get_level();
+        Self {
+            input,
+            active,
+            debounce_time,
+            level,
+        }
+    }
+
+    /// Pin level of the button while pressed.
+    pub fn active_level(&self) -> ActiveLevel {
+        self.active
+    }
+
+    /// Whether the button is held down right now, without debouncing.
+    pub fn is_pressed(&self) -> bool {
+        self.input.get_level() == self.active.pressed()
+    }
+
+    /// Wait until the button is pressed.
+    pub async fn wait_for_press(&mut self) {
+        self.wait_for_level(self.active.pressed()).await
+    }
+
+    /// Wait until the button is released.
+    pub async fn wait_for_release(&mut self) {
+        self.wait_for_level(self.active.released()).await
+    }
+
+    /// Wait for an edge towards `level` that is still at `level` after the
+    /// debounce time.
+    async fn wait_for_level(&mut self, level: Level) {
+        loop {
+            match level {
+                Level::Low => self.input.wait_for_falling_edge().await,
+                Level::High => self.input.wait_for_rising_edge().await,
+            }
+            Timer::after(self.debounce_time).await;
+            if self.input.get_level() == level {
+                return;
+            }
+        }
+    }
+
+    /// Wait for the next edge in either direction and return the new pin
+    /// level: `Level::High` for a rising edge, `Level::Low` for a falling one.
+    /// It is a press if it equals `active_level().pressed()`.
+    ///
+    /// The level is compared with the one reported by the previous call (or
+    /// the level at creation), not with the direction of the interrupt. An
+    /// edge followed by the opposite one within the debounce time is ignored,
+    /// and a change that happened while nobody was waiting is reported
+    /// immediately, so consecutive results always alternate.
+    pub async fn next_edge(&mut self) -> Level {
+        loop {
+            let level = self.input.get_level();
+            if level != self.level {
+                self.level = level;
+                return level;
+            }
+            self.input.wait_for_any_edge().await;
+            Timer::after(self.debounce_time).await;
+        }
+    }
+
+    /// Wait for a press and classify it as short or long.
+    ///
+    /// A long press is reported as soon as the button has been held for
+    /// `long_threshold`, without waiting for the release. A release exactly at
+    /// the threshold counts as long.
+    pub async fn wait_for_press_kind(&mut self, long_threshold: Duration) -> PressKind {
+        self.wait_for_press().await;
+        let pressed_at = Instant::now();
+
+        match with_timeout(long_threshold, self.wait_for_release()).await {
+            // Both instants include the debounce delay, so it cancels out
+            Ok(()) if pressed_at.elapsed() < long_threshold => PressKind::Short,
+            _ => PressKind::Long,
+        }
+    }
+
+    /// Wait for a click and report whether it was a double click.
+    ///
+    /// After the first press and release, returns `true` if a second press
+    /// follows within `window`, `false` if the window expires (a single click).
+    /// The window only starts at the release, so holding the first press for a
+    /// long time can't turn it into a double click. The second click is
+    /// consumed, including its release.
+    pub async fn wait_for_double_click(&mut self, window: Duration) -> bool {
+        self.wait_for_press().await;
+        self.wait_for_release().await;
+
+        match with_timeout(window, self.wait_for_press()).await {
+            Ok(()) => {
+                self.wait_for_release().await;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
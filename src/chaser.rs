@@ -0,0 +1,123 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bouncing light position for LED arrays.
+
+/// A light that runs along `len` LEDs and bounces back at each end.
+///
+/// The end LEDs are lit once per pass, not twice, so the light doesn't seem
+/// to pause at the ends: with 4 LEDs the positions are 0, 1, 2, 3, 2, 1, 0,
+/// 1, and so on.
+#[derive(Clone, Copy, Debug)]
+pub struct Chaser {
+    len: usize,
+    position: usize,
+    previous: usize,
+    forward: bool,
+}
+
+impl Chaser {
+    /// Start at LED 0, moving toward the last LED.
+    pub const fn new(len: usize) -> Self {
+        Self {
+            len,
+            position: 0,
+            previous: 0,
+            forward: true,
+        }
+    }
+
+    /// The LED that is lit.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The LED lit before the current one, for a fading trail.
+    ///
+    /// Equal to [`position`](Self::position) before the first step.
+    pub fn previous(&self) -> usize {
+        self.previous
+    }
+
+    /// Move the light by one LED and return its new position.
+    pub fn step(&mut self) -> usize {
+        self.previous = self.position;
+        if self.len <= 1 {
+            return self.position;
+        }
+
+        let last = self.len - 1;
+        if self.forward && self.position == last {
+            self.forward = false;
+        } else if !self.forward && self.position == 0 {
+            self.forward = true;
+        }
+        if self.forward {
+            self.position += 1;
+        } else {
+            self.position -= 1;
+        }
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions<const N: usize>(chaser: &mut Chaser) -> [usize; N] {
+        core::array::from_fn(|_| chaser.step())
+    }
+
+    #[test]
+    fn bounces_at_both_ends() {
+        let mut chaser = Chaser::new(4);
+        assert_eq!(chaser.position(), 0);
+        assert_eq!(positions(&mut chaser), [1, 2, 3, 2, 1, 0, 1, 2, 3, 2]);
+    }
+
+    #[test]
+    fn two_leds_alternate() {
+        let mut chaser = Chaser::new(2);
+        assert_eq!(positions(&mut chaser), [1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn single_or_no_led_stays_put() {
+        let mut chaser = Chaser::new(1);
+        assert_eq!(positions(&mut chaser), [0, 0, 0]);
+        let mut chaser = Chaser::new(0);
+        assert_eq!(positions(&mut chaser), [0, 0, 0]);
+    }
+
+    #[test]
+    fn previous_follows_one_step_behind() {
+        let mut chaser = Chaser::new(3);
+        assert_eq!(chaser.previous(), 0);
+        chaser.step();
+        chaser.step();
+        assert_eq!((chaser.previous(), chaser.position()), (1, 2));
+        chaser.step();
+        assert_eq!((chaser.previous(), chaser.position()), (2, 1));
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let mut chaser = Chaser::new(8);
+        for _ in 0..100 {
+            assert!(chaser.step() < 8);
+        }
+    }
+}
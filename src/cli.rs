@@ -0,0 +1,163 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Parser for simple text commands, such as those typed on a serial terminal.
+//!
+//! Commands are whitespace-delimited words, matched without regard to case:
+//! `led on`, `led off`, `blink 500`, `help`.
+
+/// Shortest accepted blink period, in milliseconds.
+pub const MIN_BLINK_MS: u32 = 50;
+/// Longest accepted blink period, in milliseconds.
+pub const MAX_BLINK_MS: u32 = 10_000;
+
+/// A parsed command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Command {
+    /// `led on` / `led off`: switch the LED steadily on or off.
+    Led(bool),
+    /// `blink <ms>`: toggle the LED every `ms` milliseconds.
+    Blink(u32),
+    /// `help`: list the commands.
+    Help,
+}
+
+/// Why a line could not be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ParseError {
+    /// The line holds no command.
+    Empty,
+    /// The first word is not a known command.
+    UnknownCommand,
+    /// The command needs an argument that is missing.
+    MissingArgument,
+    /// The argument has the wrong form, e.g. a word instead of a number.
+    InvalidArgument,
+    /// The argument is well-formed but outside the accepted range.
+    OutOfRange,
+    /// More words follow a complete command.
+    TooManyArguments,
+}
+
+impl ParseError {
+    /// Short description, suitable for an `ERR: <reason>` reply.
+    pub fn reason(self) -> &'static str {
+        match self {
+            ParseError::Empty => "empty command",
+            ParseError::UnknownCommand => "unknown command, try 'help'",
+            ParseError::MissingArgument => "missing argument",
+            ParseError::InvalidArgument => "invalid argument",
+            ParseError::OutOfRange => "argument out of range",
+            ParseError::TooManyArguments => "too many arguments",
+        }
+    }
+}
+
+impl Command {
+    /// Parse one line of input.
+    pub fn parse(line: &str) -> Result<Command, ParseError> {
+        let mut words = line.split_ascii_whitespace();
+        let name = words.next().ok_or(ParseError::Empty)?;
+
+        let command = if name.eq_ignore_ascii_case("led") {
+            let state = words.next().ok_or(ParseError::MissingArgument)?;
+            if state.eq_ignore_ascii_case("on") {
+                Command::Led(true)
+            } else if state.eq_ignore_ascii_case("off") {
+                Command::Led(false)
+            } else {
+                return Err(ParseError::InvalidArgument);
+            }
+        } else if name.eq_ignore_ascii_case("blink") {
+            let period = words.next().ok_or(ParseError::MissingArgument)?;
+            let period: u32 = period.parse().map_err(|_| ParseError::InvalidArgument)?;
+            if !(MIN_BLINK_MS..=MAX_BLINK_MS).contains(&period) {
+                return Err(ParseError::OutOfRange);
+            }
+            Command::Blink(period)
+        } else if name.eq_ignore_ascii_case("help") {
+            Command::Help
+        } else {
+            return Err(ParseError::UnknownCommand);
+        };
+
+        if words.next().is_some() {
+            return Err(ParseError::TooManyArguments);
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn led_commands() {
+        assert_eq!(Command::parse("led on"), Ok(Command::Led(true)));
+        assert_eq!(Command::parse("led off"), Ok(Command::Led(false)));
+    }
+
+    #[test]
+    fn blink_with_period() {
+        assert_eq!(Command::parse("blink 500"), Ok(Command::Blink(500)));
+        assert_eq!(Command::parse("blink 50"), Ok(Command::Blink(MIN_BLINK_MS)));
+        assert_eq!(Command::parse("blink 10000"), Ok(Command::Blink(MAX_BLINK_MS)));
+    }
+
+    #[test]
+    fn case_and_whitespace_are_ignored() {
+        assert_eq!(Command::parse("  LED   On \t"), Ok(Command::Led(true)));
+        assert_eq!(Command::parse("Help"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn empty_lines() {
+        assert_eq!(Command::parse(""), Err(ParseError::Empty));
+        assert_eq!(Command::parse("   "), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert_eq!(Command::parse("dance"), Err(ParseError::UnknownCommand));
+        assert_eq!(Command::parse("ledon"), Err(ParseError::UnknownCommand));
+    }
+
+    #[test]
+    fn missing_arguments() {
+        assert_eq!(Command::parse("led"), Err(ParseError::MissingArgument));
+        assert_eq!(Command::parse("blink"), Err(ParseError::MissingArgument));
+    }
+
+    #[test]
+    fn malformed_arguments() {
+        assert_eq!(Command::parse("led maybe"), Err(ParseError::InvalidArgument));
+        assert_eq!(Command::parse("blink fast"), Err(ParseError::InvalidArgument));
+        assert_eq!(Command::parse("blink -5"), Err(ParseError::InvalidArgument));
+        assert_eq!(Command::parse("blink 99999999999"), Err(ParseError::InvalidArgument));
+    }
+
+    #[test]
+    fn out_of_range_period() {
+        assert_eq!(Command::parse("blink 0"), Err(ParseError::OutOfRange));
+        assert_eq!(Command::parse("blink 10001"), Err(ParseError::OutOfRange));
+    }
+
+    #[test]
+    fn extra_words_are_rejected() {
+        assert_eq!(Command::parse("led on now"), Err(ParseError::TooManyArguments));
+        assert_eq!(Command::parse("help me"), Err(ParseError::TooManyArguments));
+    }
+}
@@ -0,0 +1,65 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! System clock configurations.
+
+use embassy_stm32::rcc::{
+    AHBPrescaler, APBPrescaler, Hse, HseMode, Pll, PllMul, PllPDiv, PllPreDiv, PllQDiv, PllSource, Sysclk,
+};
+use embassy_stm32::time::mhz;
+use embassy_stm32::Config;
+
+/// Run the core at its maximum 84 MHz from the 8 MHz ST-LINK clock.
+///
+/// Bus clocks: AHB 84 MHz, APB1 42 MHz (timers 84 MHz), APB2 84 MHz. The PLL
+/// Q output gives the exact 48 MHz needed by USB. Section numbers refer to
+/// the STM32F401 reference manual (RM0368).
+pub fn max_performance_config() -> Config {
+    let mut config = Config::default();
+
+    // 6.2.1: on the NucleoF401RE the HSE input is the 8 MHz MCO output of the
+    // ST-LINK (solder bridge SB50), an external clock rather than a crystal,
+    // so the oscillator is bypassed
+    config.rcc.hse = Some(Hse {
+        freq: mhz(8),
+        mode: HseMode::Bypass,
+    });
+
+    // 6.3.2 RCC_PLLCFGR: the PLL input must be 1-2 MHz (8 MHz / PLLM 4 =
+    // 2 MHz) and the VCO output 192-432 MHz (2 MHz * PLLN 168 = 336 MHz).
+    // PLLP divides it to 84 MHz for SYSCLK, PLLQ to 48 MHz for USB.
+    config.rcc.pll_src = PllSource::HSE;
+    config.rcc.pll = Some(Pll {
+        prediv: PllPreDiv::DIV4,
+        mul: PllMul::MUL168,
+        divp: Some(PllPDiv::DIV4),
+        divq: Some(PllQDiv::DIV7),
+        divr: None,
+    });
+
+    // 6.3.3 RCC_CFGR SW: switch SYSCLK to the PLL P output
+    config.rcc.sys = Sysclk::PLL1_P;
+
+    // 6.3.3 RCC_CFGR HPRE/PPRE1/PPRE2: APB1 is limited to 42 MHz, AHB and
+    // APB2 can run at the full 84 MHz. When an APB prescaler is not 1, the
+    // timers on that bus run at twice the bus clock (6.2, figure 12).
+    config.rcc.ahb_pre = AHBPrescaler::DIV1;
+    config.rcc.apb1_pre = APBPrescaler::DIV2;
+    config.rcc.apb2_pre = APBPrescaler::DIV1;
+
+    // Flash wait states (3.4.1: 2 WS at 84 MHz and 2.7-3.6 V) and the voltage
+    // scale (PWR_CR VOS, 5.4.1) are set by embassy_stm32::init from the result
+    config
+}
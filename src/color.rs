@@ -0,0 +1,102 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Color conversions for RGB LEDs.
+
+/// Convert a color from HSV to RGB duty cycles.
+///
+/// `hue` is in degrees (wrapped to 0–360), `saturation` and `value` in
+/// 0.0–1.0. Each returned component is in `0..=max_duty`, ready for
+/// `set_duty_cycle`.
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32, max_duty: u16) -> [u16; 3] {
+    let hue = hue % 360.0;
+    let hue = if hue < 0.0 { hue + 360.0 } else { hue };
+    let saturation = saturation.clamp(0.0, 1.0);
+    let value = value.clamp(0.0, 1.0);
+
+    // The hue wheel is split in six 60° sectors; in each one a component is
+    // at its maximum, one at its minimum and one ramps between them
+    let chroma = value * saturation;
+    let sector = hue / 60.0;
+    let ramp = chroma * (1.0 - ((sector % 2.0) - 1.0).abs());
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, ramp, 0.0),
+        1 => (ramp, chroma, 0.0),
+        2 => (0.0, chroma, ramp),
+        3 => (0.0, ramp, chroma),
+        4 => (ramp, 0.0, chroma),
+        _ => (chroma, 0.0, ramp),
+    };
+    let min = value - chroma;
+    let scale = |c: f32| ((c + min) * max_duty as f32 + 0.5) as u16;
+    [scale(r), scale(g), scale(b)]
+}
+
+/// Mix two colors: `t` = 0.0 gives `from`, 1.0 gives `to`.
+///
+/// Stepping `t` from 0.0 to 1.0 over time fades linearly between the colors.
+pub fn blend(from: [u16; 3], to: [u16; 3], t: f32) -> [u16; 3] {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u16, b: u16| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u16;
+    [mix(from[0], to[0]), mix(from[1], to[1]), mix(from[2], to[2])]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: u16 = 1000;
+
+    #[test]
+    fn primary_colors() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0, MAX), [MAX, 0, 0]);
+        assert_eq!(hsv_to_rgb(120.0, 1.0, 1.0, MAX), [0, MAX, 0]);
+        assert_eq!(hsv_to_rgb(240.0, 1.0, 1.0, MAX), [0, 0, MAX]);
+    }
+
+    #[test]
+    fn secondary_colors() {
+        assert_eq!(hsv_to_rgb(60.0, 1.0, 1.0, MAX), [MAX, MAX, 0]);
+        assert_eq!(hsv_to_rgb(180.0, 1.0, 1.0, MAX), [0, MAX, MAX]);
+        assert_eq!(hsv_to_rgb(300.0, 1.0, 1.0, MAX), [MAX, 0, MAX]);
+    }
+
+    #[test]
+    fn hue_wraps_around() {
+        assert_eq!(hsv_to_rgb(360.0, 1.0, 1.0, MAX), hsv_to_rgb(0.0, 1.0, 1.0, MAX));
+        assert_eq!(hsv_to_rgb(-120.0, 1.0, 1.0, MAX), hsv_to_rgb(240.0, 1.0, 1.0, MAX));
+    }
+
+    #[test]
+    fn zero_saturation_is_grey() {
+        assert_eq!(hsv_to_rgb(200.0, 0.0, 0.5, MAX), [500, 500, 500]);
+    }
+
+    #[test]
+    fn value_scales_brightness() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 0.0, MAX), [0, 0, 0]);
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 0.25, MAX), [250, 0, 0]);
+    }
+
+    #[test]
+    fn blend_end_points_and_middle() {
+        let from = [0, 1000, 200];
+        let to = [1000, 0, 200];
+        assert_eq!(blend(from, to, 0.0), from);
+        assert_eq!(blend(from, to, 1.0), to);
+        assert_eq!(blend(from, to, 0.5), [500, 500, 200]);
+        assert_eq!(blend(from, to, 2.0), to);
+    }
+}
@@ -0,0 +1,334 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Settings stored in flash with simple wear leveling.
+//!
+//! Erasing a flash sector wears it out (about 10 000 cycles), and erasing
+//! 128 KiB on every save would also be slow. Instead, each save appends a
+//! small record after the previous ones, and the sector is only erased once
+//! it is full. At boot the sector is scanned and the last valid record wins.
+//! A magic number and a CRC tell valid records from blank, torn or foreign
+//! data, so a corrupted sector falls back to the defaults.
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::flash::{Blocking, Error, Flash, WRITE_SIZE};
+
+/// First byte of the config sector, relative to the start of flash.
+///
+/// Sector 7, the last 128 KiB of the 512 KiB flash, as in `_27_flash_storage`.
+pub const SECTOR_START: u32 = 0x6_0000;
+/// End of the config sector, relative to the start of flash.
+pub const SECTOR_END: u32 = 0x8_0000;
+/// Size of one record, in bytes.
+pub const RECORD_SIZE: usize = 32;
+/// Number of records that fit in the sector before it must be erased.
+pub const SLOTS: usize = (SECTOR_END - SECTOR_START) as usize / RECORD_SIZE;
+
+/// Marks the start of a config record.
+pub const MAGIC: u32 = 0xC0F1_6001;
+
+// Record layout, little-endian: magic, sequence, config, padding, CRC of
+// everything before it
+const CONFIG_OFFSET: usize = 8;
+const CRC_OFFSET: usize = RECORD_SIZE - 4;
+
+/// Application settings kept across resets.
+#[derive(Clone, Copy, Debug, PartialEq, defmt::Format)]
+pub struct Config {
+    /// Servo pulse width at 0°, in microseconds.
+    pub servo_min_us: u16,
+    /// Servo pulse width at 180°, in microseconds.
+    pub servo_max_us: u16,
+    /// Alarm on above this temperature, in °C.
+    pub alarm_on_c: f32,
+    /// Alarm off again below this temperature, in °C.
+    pub alarm_off_c: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            servo_min_us: crate::servo::DEFAULT_MIN_US as u16,
+            servo_max_us: crate::servo::DEFAULT_MAX_US as u16,
+            alarm_on_c: 35.0,
+            alarm_off_c: 33.0,
+        }
+    }
+}
+
+/// A config as stored in flash, with its position in the save history.
+#[derive(Clone, Copy, Debug, PartialEq, defmt::Format)]
+pub struct Record {
+    /// Incremented on every save.
+    pub sequence: u32,
+    /// The stored settings.
+    pub config: Config,
+}
+
+impl Record {
+    /// Serialize the record, including magic and CRC.
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut bytes = [0xFF; RECORD_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.sequence.to_le_bytes());
+        let config = &mut bytes[CONFIG_OFFSET..];
+        config[0..2].copy_from_slice(&self.config.servo_min_us.to_le_bytes());
+        config[2..4].copy_from_slice(&self.config.servo_max_us.to_le_bytes());
+        config[4..8].copy_from_slice(&self.config.alarm_on_c.to_le_bytes());
+        config[8..12].copy_from_slice(&self.config.alarm_off_c.to_le_bytes());
+        let crc = crc32(&bytes[..CRC_OFFSET]);
+        bytes[CRC_OFFSET..].copy_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Parse a record, or `None` if the magic or the CRC doesn't match.
+    pub fn decode(bytes: &[u8; RECORD_SIZE]) -> Option<Self> {
+        let word = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+        let half = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+        if word(0) != MAGIC || word(CRC_OFFSET) != crc32(&bytes[..CRC_OFFSET]) {
+            return None;
+        }
+        Some(Self {
+            sequence: word(4),
+            config: Config {
+                servo_min_us: half(CONFIG_OFFSET),
+                servo_max_us: half(CONFIG_OFFSET + 2),
+                alarm_on_c: f32::from_bits(word(CONFIG_OFFSET + 4)),
+                alarm_off_c: f32::from_bits(word(CONFIG_OFFSET + 8)),
+            },
+        })
+    }
+}
+
+/// CRC-32 (IEEE 802.3), computed bit by bit.
+///
+/// Slow compared to a table or the CRC unit, but records are tiny and only
+/// checked at boot.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Finds the latest record and the first free slot, one slot at a time.
+///
+/// Slots are fed in order with [`push`](Scan::push). Records are only ever
+/// appended, so the first blank slot ends the used part of the sector. Slots
+/// that are neither blank nor valid, e.g. a save interrupted by a reset, are
+/// skipped.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Scan {
+    latest: Option<Record>,
+    next_free: Option<usize>,
+    slots_seen: usize,
+}
+
+impl Scan {
+    /// Start a scan at slot 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next slot; returns `false` once the scan is complete.
+    pub fn push(&mut self, slot: &[u8; RECORD_SIZE]) -> bool {
+        if self.next_free.is_some() {
+            return false;
+        }
+        if slot.iter().all(|&b| b == 0xFF) {
+            self.next_free = Some(self.slots_seen);
+            return false;
+        }
+        if let Some(record) = Record::decode(slot) {
+            self.latest = Some(record);
+        }
+        self.slots_seen += 1;
+        true
+    }
+
+    /// The last valid record seen, or `None` for a blank or corrupted sector.
+    pub fn latest(&self) -> Option<Record> {
+        self.latest
+    }
+
+    /// The first blank slot, or `None` if none was found (sector full).
+    pub fn next_free(&self) -> Option<usize> {
+        self.next_free
+    }
+}
+
+/// Config storage in the flash sector from [`SECTOR_START`] to [`SECTOR_END`].
+#[cfg(target_arch = "arm")]
+pub struct FlashConfigStore<'d> {
+    flash: Flash<'d, Blocking>,
+    scan: Scan,
+}
+
+#[cfg(target_arch = "arm")]
+impl<'d> FlashConfigStore<'d> {
+    /// Scan the sector for the latest record and the next free slot.
+    pub fn open(mut flash: Flash<'d, Blocking>) -> Result<Self, Error> {
+        let mut scan = Scan::new();
+        let mut slot = [0; RECORD_SIZE];
+        for index in 0..SLOTS {
+            flash.blocking_read(slot_offset(index), &mut slot)?;
+            if !scan.push(&slot) {
+                break;
+            }
+        }
+        Ok(Self { flash, scan })
+    }
+
+    /// The stored config, or the defaults if there is none.
+    pub fn config(&self) -> Config {
+        self.scan.latest().map(|record| record.config).unwrap_or_default()
+    }
+
+    /// The latest valid record, if any.
+    pub fn latest(&self) -> Option<Record> {
+        self.scan.latest()
+    }
+
+    /// Append `config` as a new record and return the slot it went to.
+    ///
+    /// Erases the sector first when it is full, which blocks the CPU for
+    /// about a second.
+    pub fn save(&mut self, config: &Config) -> Result<usize, Error> {
+        const _: () = assert!(RECORD_SIZE % WRITE_SIZE == 0);
+
+        let slot = match self.scan.next_free() {
+            Some(slot) => slot,
+            None => {
+                self.flash.blocking_erase(SECTOR_START, SECTOR_END)?;
+                0
+            }
+        };
+        let sequence = self.scan.latest().map_or(0, |record| record.sequence.wrapping_add(1));
+        let record = Record {
+            sequence,
+            config: *config,
+        };
+        self.flash.blocking_write(slot_offset(slot), &record.encode())?;
+
+        // Same state a new scan would find
+        self.scan = Scan {
+            latest: Some(record),
+            next_free: (slot + 1 < SLOTS).then_some(slot + 1),
+            slots_seen: slot + 1,
+        };
+        Ok(slot)
+    }
+}
+
+#[cfg(target_arch = "arm")]
+fn slot_offset(index: usize) -> u32 {
+    SECTOR_START + (index * RECORD_SIZE) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLANK: [u8; RECORD_SIZE] = [0xFF; RECORD_SIZE];
+
+    fn record(sequence: u32, servo_max_us: u16) -> [u8; RECORD_SIZE] {
+        let config = Config {
+            servo_max_us,
+            ..Config::default()
+        };
+        Record { sequence, config }.encode()
+    }
+
+    fn scan(slots: &[[u8; RECORD_SIZE]]) -> Scan {
+        let mut scan = Scan::new();
+        for slot in slots {
+            if !scan.push(slot) {
+                break;
+            }
+        }
+        scan
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let record = Record {
+            sequence: 7,
+            config: Config {
+                servo_min_us: 550,
+                servo_max_us: 2450,
+                alarm_on_c: 40.5,
+                alarm_off_c: -3.25,
+            },
+        };
+        assert_eq!(Record::decode(&record.encode()), Some(record));
+    }
+
+    #[test]
+    fn corrupted_record_is_rejected() {
+        let mut bytes = record(1, 2000);
+        bytes[9] ^= 0x01;
+        assert_eq!(Record::decode(&bytes), None);
+        assert_eq!(Record::decode(&BLANK), None);
+    }
+
+    #[test]
+    fn blank_sector_has_no_record() {
+        let scan = scan(&[BLANK, BLANK]);
+        assert_eq!(scan.latest(), None);
+        assert_eq!(scan.next_free(), Some(0));
+    }
+
+    #[test]
+    fn last_record_before_blank_wins() {
+        let scan = scan(&[record(0, 2000), record(1, 2100), record(2, 2200), BLANK, BLANK]);
+        assert_eq!(scan.latest().map(|r| r.config.servo_max_us), Some(2200));
+        assert_eq!(scan.next_free(), Some(3));
+    }
+
+    #[test]
+    fn torn_record_falls_back_to_previous() {
+        let mut torn = record(1, 2100);
+        torn[20..].fill(0xFF);
+        let scan = scan(&[record(0, 2000), torn, BLANK]);
+        assert_eq!(scan.latest().map(|r| r.sequence), Some(0));
+        assert_eq!(scan.next_free(), Some(2));
+    }
+
+    #[test]
+    fn foreign_data_is_skipped() {
+        let mut garbage = BLANK;
+        garbage[..8].copy_from_slice(&[0xDE, 0xC0, 0x07, 0xB0, 1, 0, 0, 0]);
+        let scan = scan(&[garbage, BLANK]);
+        assert_eq!(scan.latest(), None);
+        assert_eq!(scan.next_free(), Some(1));
+    }
+
+    #[test]
+    fn full_sector_has_no_free_slot() {
+        let scan = scan(&[record(0, 2000), record(1, 2100)]);
+        assert_eq!(scan.latest().map(|r| r.sequence), Some(1));
+        assert_eq!(scan.next_free(), None);
+    }
+}
@@ -0,0 +1,406 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Closed-loop control and sensor fusion.
+
+use micromath::F32Ext;
+
+/// PID controller.
+///
+/// Each call to [`update`](Self::update) compares the measured value with
+/// the setpoint and returns the output that drives the measurement toward
+/// it, as the sum of three terms:
+///
+/// - **P**roportional: `kp` times the current error.
+/// - **I**ntegral: `ki` times the error accumulated over time; removes the
+///   steady-state error that P alone leaves.
+/// - **D**erivative: `kd` times the rate of change of the measurement;
+///   damps overshoot. It is taken on the measurement rather than on the
+///   error, so a step of the setpoint doesn't cause an output spike.
+///
+/// The output is clamped to `output_min..=output_max`. The integral term is
+/// clamped to the same range (anti-windup): while the output is saturated,
+/// e.g. a motor at full power that can't reach the setpoint, the integral
+/// can't grow without bound and then overshoot for a long time once the
+/// setpoint becomes reachable again.
+#[derive(Clone, Copy, Debug)]
+pub struct Pid {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    output_min: f32,
+    output_max: f32,
+    integral: f32,
+    last_measured: Option<f32>,
+}
+
+impl Pid {
+    /// Create a controller with the gains `kp`, `ki`, `kd` and the output
+    /// range `output_min..=output_max`.
+    ///
+    /// The limits are swapped if `output_min` is greater than `output_max`.
+    pub fn new(kp: f32, ki: f32, kd: f32, output_min: f32, output_max: f32) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            output_min: output_min.min(output_max),
+            output_max: output_min.max(output_max),
+            integral: 0.0,
+            last_measured: None,
+        }
+    }
+
+    /// Run one control step and return the new output.
+    ///
+    /// `dt` is the time since the previous call, in seconds. Call it at a
+    /// fixed rate, e.g. from a `Ticker`. With `dt` of zero or less the
+    /// integral is held instead of accumulated, and the derivative is skipped:
+    /// the output is the proportional term plus the integral so far.
+    pub fn update(&mut self, setpoint: f32, measured: f32, dt: f32) -> f32 {
+        let error = setpoint - measured;
+        let mut derivative = 0.0;
+        if dt > 0.0 {
+            self.integral = (self.integral + self.ki * error * dt).clamp(self.output_min, self.output_max);
+            if let Some(last) = self.last_measured {
+                derivative = -(measured - last) / dt;
+            }
+        }
+        self.last_measured = Some(measured);
+
+        (self.kp * error + self.integral + self.kd * derivative).clamp(self.output_min, self.output_max)
+    }
+
+    /// Forget the accumulated integral and the last measurement.
+    ///
+    /// Call it when the loop was stopped for a while, so the controller
+    /// restarts without a stale integral or a derivative spike.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_measured = None;
+    }
+}
+
+/// Orientation relative to the horizontal, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq, defmt::Format)]
+pub struct Tilt {
+    /// Rotation around the Y axis; positive when the X axis tips down, the
+    /// direction of a positive rotation rate around Y.
+    pub pitch: f32,
+    /// Rotation around the X axis; positive when the Y axis tips up, the
+    /// direction of a positive rotation rate around X.
+    pub roll: f32,
+}
+
+/// Tilt from the acceleration on the three axes of a sensor at rest.
+///
+/// At rest the accelerometer only measures gravity, which points straight
+/// down: the direction of the measured vector gives the tilt. The units of
+/// `x`, `y` and `z` don't matter, only their ratios. Movement adds its own
+/// acceleration to gravity, so the result is only right while the sensor is
+/// still or moving at constant speed.
+///
+/// Pitch is limited to ±90°. Roll covers ±180°, but is meaningless when
+/// pitch is close to ±90°, with Y and Z both near zero.
+pub fn tilt_from_acceleration(x: f32, y: f32, z: f32) -> Tilt {
+    Tilt {
+        pitch: (-x).atan2((y * y + z * z).sqrt()).to_degrees(),
+        roll: y.atan2(z).to_degrees(),
+    }
+}
+
+/// Complementary filter fusing an absolute angle with an angular rate.
+///
+/// The angle from the accelerometer is right on average but noisy, and
+/// disturbed by every movement. Integrating the gyroscope rate gives a
+/// smooth angle that follows fast movements, but any rate offset makes it
+/// drift further away over time. The filter takes the short-term changes
+/// from the rate and the long-term value from the absolute angle:
+///
+/// ```text
+/// angle = a * (angle + rate * dt) + (1 - a) * absolute,   a = tc / (tc + dt)
+/// ```
+///
+/// The time constant `tc` sets the crossover: changes faster than it come
+/// from the rate, slower ones from the absolute angle.
+#[derive(Clone, Copy, Debug)]
+pub struct ComplementaryFilter {
+    time_constant: f32,
+    angle: Option<f32>,
+}
+
+impl ComplementaryFilter {
+    /// Create a filter with a time constant in seconds; a negative value is
+    /// treated as zero, which passes the absolute angle through unchanged.
+    pub fn new(time_constant: f32) -> Self {
+        Self {
+            time_constant: time_constant.max(0.0),
+            angle: None,
+        }
+    }
+
+    /// Add an absolute angle and the rate measured over the last `dt`
+    /// seconds, and return the new estimate.
+    ///
+    /// The first call returns `absolute`, so the estimate doesn't have to
+    /// settle from zero. `rate` must be in the unit of the angle per second.
+    pub fn update(&mut self, absolute: f32, rate: f32, dt: f32) -> f32 {
+        let angle = match self.angle {
+            None => absolute,
+            Some(angle) => {
+                let dt = dt.max(0.0);
+                let total = self.time_constant + dt;
+                if total > 0.0 {
+                    let a = self.time_constant / total;
+                    a * (angle + rate * dt) + (1.0 - a) * absolute
+                } else {
+                    absolute
+                }
+            }
+        };
+        self.angle = Some(angle);
+        angle
+    }
+
+    /// The current estimate, or `None` before the first update.
+    pub fn angle(&self) -> Option<f32> {
+        self.angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.01;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn proportional_only() {
+        let mut pid = Pid::new(2.0, 0.0, 0.0, -100.0, 100.0);
+        assert_close(pid.update(10.0, 4.0, DT), 12.0);
+        assert_close(pid.update(10.0, 13.0, DT), -6.0);
+        assert_close(pid.update(10.0, 10.0, DT), 0.0);
+    }
+
+    #[test]
+    fn integral_accumulates_error_over_time() {
+        let mut pid = Pid::new(0.0, 10.0, 0.0, -100.0, 100.0);
+        // 10 * 2.0 * 0.01 = 0.2 per step
+        assert_close(pid.update(2.0, 0.0, DT), 0.2);
+        assert_close(pid.update(2.0, 0.0, DT), 0.4);
+        // No error: the integral holds its value
+        assert_close(pid.update(2.0, 2.0, DT), 0.4);
+        // Negative error winds it back
+        assert_close(pid.update(0.0, 2.0, DT), 0.2);
+    }
+
+    #[test]
+    fn derivative_opposes_the_change_of_the_measurement() {
+        let mut pid = Pid::new(0.0, 0.0, 0.5, -100.0, 100.0);
+        // No previous measurement, no derivative
+        assert_close(pid.update(0.0, 0.0, DT), 0.0);
+        // Rising by 0.1 in 0.01 s: -0.5 * 10
+        assert_close(pid.update(0.0, 0.1, DT), -5.0);
+        assert_close(pid.update(0.0, 0.1, DT), 0.0);
+    }
+
+    #[test]
+    fn setpoint_step_does_not_kick_the_derivative() {
+        let mut pid = Pid::new(0.0, 0.0, 1.0, -100.0, 100.0);
+        pid.update(0.0, 5.0, DT);
+        assert_close(pid.update(50.0, 5.0, DT), 0.0);
+    }
+
+    #[test]
+    fn output_is_clamped() {
+        let mut pid = Pid::new(10.0, 0.0, 0.0, 0.0, 100.0);
+        assert_eq!(pid.update(1000.0, 0.0, DT), 100.0);
+        assert_eq!(pid.update(0.0, 1000.0, DT), 0.0);
+    }
+
+    #[test]
+    fn swapped_limits() {
+        let mut pid = Pid::new(10.0, 0.0, 0.0, 50.0, -50.0);
+        assert_eq!(pid.update(1000.0, 0.0, DT), 50.0);
+        assert_eq!(pid.update(-1000.0, 0.0, DT), -50.0);
+    }
+
+    #[test]
+    fn integral_does_not_wind_up_while_saturated() {
+        let mut pid = Pid::new(0.0, 100.0, 0.0, -10.0, 10.0);
+        // A long time with a large, unreachable error
+        for _ in 0..10_000 {
+            pid.update(100.0, 0.0, DT);
+        }
+        // The integral stopped at the limit, so a reversed error brings the
+        // output back down right away instead of after another 10_000 steps
+        let output = pid.update(0.0, 100.0, DT);
+        assert!(output < 10.0);
+        for _ in 0..3 {
+            pid.update(0.0, 100.0, DT);
+        }
+        assert!(pid.update(0.0, 100.0, DT) < 0.0);
+    }
+
+    #[test]
+    fn zero_dt_skips_integral_and_derivative_updates() {
+        let mut pid = Pid::new(1.0, 100.0, 100.0, -100.0, 100.0);
+        pid.update(0.0, 0.0, DT);
+        assert_close(pid.update(5.0, 2.0, 0.0), 3.0);
+        assert_close(pid.update(5.0, 2.0, -1.0), 3.0);
+    }
+
+    #[test]
+    fn zero_dt_holds_the_accumulated_integral() {
+        let mut pid = Pid::new(1.0, 100.0, 100.0, -100.0, 100.0);
+        // Integral of 100 * 2 * 0.01 = 2 after one step
+        pid.update(2.0, 0.0, DT);
+        // P of 3 plus the held integral, no derivative for the jump to 2
+        assert_close(pid.update(5.0, 2.0, 0.0), 5.0);
+        assert_close(pid.update(5.0, 2.0, -1.0), 5.0);
+    }
+
+    #[test]
+    fn reset_clears_integral_and_derivative_history() {
+        let mut pid = Pid::new(0.0, 10.0, 1.0, -100.0, 100.0);
+        for _ in 0..10 {
+            pid.update(5.0, 0.0, DT);
+        }
+        pid.reset();
+        // No integral left, and a jump of the measurement right after the
+        // reset does not count as a derivative
+        assert_close(pid.update(0.0, 50.0, DT), -5.0);
+    }
+
+    #[test]
+    fn closed_loop_reaches_the_setpoint() {
+        // First-order plant: the speed follows 20 times the output with a
+        // 0.1 s time constant
+        let mut pid = Pid::new(0.02, 0.5, 0.0, 0.0, 100.0);
+        let mut speed = 0.0;
+        for _ in 0..500 {
+            let output = pid.update(1000.0, speed, DT);
+            speed += (20.0 * output - speed) * DT / 0.1;
+        }
+        assert!((speed - 1000.0).abs() < 1.0, "speed {}", speed);
+    }
+
+    fn assert_angle(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.5, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn flat_sensor_is_level() {
+        let tilt = tilt_from_acceleration(0.0, 0.0, 1.0);
+        assert_angle(tilt.pitch, 0.0);
+        assert_angle(tilt.roll, 0.0);
+    }
+
+    #[test]
+    fn pitch_from_rotation_around_y() {
+        // Rotated 30 degrees around Y: gravity moves from Z toward -X
+        let tilt = tilt_from_acceleration(-0.5, 0.0, 0.866);
+        assert_angle(tilt.pitch, 30.0);
+        assert_angle(tilt.roll, 0.0);
+        let tilt = tilt_from_acceleration(0.5, 0.0, 0.866);
+        assert_angle(tilt.pitch, -30.0);
+    }
+
+    #[test]
+    fn roll_from_rotation_around_x() {
+        // Rotated 45 degrees around X: gravity moves from Z toward +Y
+        let tilt = tilt_from_acceleration(0.0, 0.707, 0.707);
+        assert_angle(tilt.pitch, 0.0);
+        assert_angle(tilt.roll, 45.0);
+        let tilt = tilt_from_acceleration(0.0, -0.707, 0.707);
+        assert_angle(tilt.roll, -45.0);
+    }
+
+    #[test]
+    fn upside_down_and_on_edge() {
+        let tilt = tilt_from_acceleration(0.0, 0.0, -1.0);
+        assert_angle(tilt.pitch, 0.0);
+        assert_angle(tilt.roll.abs(), 180.0);
+        assert_angle(tilt_from_acceleration(-1.0, 0.0, 0.0).pitch, 90.0);
+        assert_angle(tilt_from_acceleration(0.0, 1.0, 0.0).roll, 90.0);
+    }
+
+    #[test]
+    fn tilt_ignores_the_magnitude() {
+        let small = tilt_from_acceleration(-0.25, 0.1, 0.4);
+        let large = tilt_from_acceleration(-2.5, 1.0, 4.0);
+        assert_angle(small.pitch, large.pitch);
+        assert_angle(small.roll, large.roll);
+    }
+
+    #[test]
+    fn filter_starts_at_the_first_absolute_angle() {
+        let mut filter = ComplementaryFilter::new(0.5);
+        assert_eq!(filter.angle(), None);
+        assert_eq!(filter.update(12.0, 100.0, DT), 12.0);
+        assert_eq!(filter.angle(), Some(12.0));
+    }
+
+    #[test]
+    fn filter_follows_the_rate_in_the_short_term() {
+        let mut filter = ComplementaryFilter::new(1.0);
+        filter.update(0.0, 0.0, DT);
+        // Turning at 90 degrees/s for 0.1 s while the absolute angle is
+        // still wrong, e.g. disturbed by the movement itself
+        for _ in 0..10 {
+            filter.update(0.0, 90.0, DT);
+        }
+        let angle = filter.angle().unwrap();
+        assert!(angle > 8.0 && angle < 9.0, "angle {}", angle);
+    }
+
+    #[test]
+    fn filter_converges_to_the_absolute_angle() {
+        // A constant rate offset, as from an uncalibrated gyroscope, leaves
+        // only a small error instead of drifting away
+        let mut filter = ComplementaryFilter::new(0.5);
+        filter.update(0.0, 0.0, DT);
+        for _ in 0..1000 {
+            filter.update(20.0, 1.0, DT);
+        }
+        let angle = filter.angle().unwrap();
+        assert!((angle - 20.5).abs() < 0.1, "angle {}", angle);
+    }
+
+    #[test]
+    fn filter_smooths_noise() {
+        let mut filter = ComplementaryFilter::new(0.5);
+        filter.update(10.0, 0.0, DT);
+        let mut max_error: f32 = 0.0;
+        for i in 0..500 {
+            let noise = if i % 2 == 0 { 5.0 } else { -5.0 };
+            let angle = filter.update(10.0 + noise, 0.0, DT);
+            max_error = max_error.max((angle - 10.0).abs());
+        }
+        assert!(max_error < 0.2, "max error {}", max_error);
+    }
+
+    #[test]
+    fn zero_time_constant_passes_the_absolute_angle() {
+        let mut filter = ComplementaryFilter::new(-1.0);
+        filter.update(0.0, 0.0, DT);
+        assert_eq!(filter.update(42.0, 100.0, DT), 42.0);
+        assert_eq!(filter.update(-7.0, 100.0, 0.0), -7.0);
+    }
+}
@@ -0,0 +1,153 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Easing curves for animations.
+//!
+//! An easing function maps the elapsed fraction of an animation, 0.0 to 1.0,
+//! to the fraction of the distance covered. A linear curve moves at constant
+//! speed, the others start and/or end gently, which looks more natural for
+//! fades and movements.
+
+/// Constant speed.
+pub fn linear(t: f32) -> f32 {
+    t.clamp(0.0, 1.0)
+}
+
+/// Start slowly, then accelerate (cubic).
+pub fn ease_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * t
+}
+
+/// Start fast, then decelerate (cubic).
+pub fn ease_out(t: f32) -> f32 {
+    1.0 - ease_in(1.0 - t)
+}
+
+/// Accelerate during the first half, decelerate during the second (cubic).
+pub fn ease_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        ease_in(2.0 * t) / 2.0
+    } else {
+        0.5 + ease_out(2.0 * t - 1.0) / 2.0
+    }
+}
+
+/// Move from `from` to `to`: `progress` = 0.0 gives `from`, 1.0 gives `to`.
+///
+/// Feed it the output of an easing function to animate a duty cycle.
+pub fn interpolate(from: u16, to: u16, progress: f32) -> u16 {
+    let progress = progress.clamp(0.0, 1.0);
+    (from as f32 + (to as f32 - from as f32) * progress + 0.5) as u16
+}
+
+/// A selectable easing curve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Easing {
+    /// See [`linear`].
+    Linear,
+    /// See [`ease_in`].
+    EaseIn,
+    /// See [`ease_out`].
+    EaseOut,
+    /// See [`ease_in_out`].
+    EaseInOut,
+}
+
+impl Easing {
+    /// Apply the curve to the elapsed fraction `t`.
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => linear(t),
+            Easing::EaseIn => ease_in(t),
+            Easing::EaseOut => ease_out(t),
+            Easing::EaseInOut => ease_in_out(t),
+        }
+    }
+
+    /// The next curve, wrapping around after the last one.
+    pub fn next(self) -> Self {
+        match self {
+            Easing::Linear => Easing::EaseIn,
+            Easing::EaseIn => Easing::EaseOut,
+            Easing::EaseOut => Easing::EaseInOut,
+            Easing::EaseInOut => Easing::Linear,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 4] = [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut];
+
+    #[test]
+    fn end_points() {
+        for easing in ALL {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn monotonic() {
+        for easing in ALL {
+            let mut previous = easing.apply(0.0);
+            for i in 1..=1000 {
+                let value = easing.apply(i as f32 / 1000.0);
+                assert!(value >= previous, "{:?} decreases at step {}", easing, i);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn clamps_out_of_range_input() {
+        for easing in ALL {
+            assert_eq!(easing.apply(-0.5), 0.0);
+            assert_eq!(easing.apply(1.5), 1.0);
+        }
+    }
+
+    #[test]
+    fn curve_shapes() {
+        assert_eq!(linear(0.25), 0.25);
+        assert!(ease_in(0.25) < 0.25);
+        assert!(ease_out(0.25) > 0.25);
+        assert_eq!(ease_in_out(0.5), 0.5);
+        assert!(ease_in_out(0.25) < 0.25);
+        assert!(ease_in_out(0.75) > 0.75);
+    }
+
+    #[test]
+    fn next_cycles_through_all_curves() {
+        let mut easing = Easing::Linear;
+        for expected in ALL.iter().cycle().skip(1).take(4) {
+            easing = easing.next();
+            assert_eq!(easing, *expected);
+        }
+    }
+
+    #[test]
+    fn interpolate_both_directions() {
+        assert_eq!(interpolate(100, 900, 0.0), 100);
+        assert_eq!(interpolate(100, 900, 0.5), 500);
+        assert_eq!(interpolate(100, 900, 1.0), 900);
+        assert_eq!(interpolate(900, 100, 0.25), 700);
+        assert_eq!(interpolate(0, 1000, 2.0), 1000);
+    }
+}
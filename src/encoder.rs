@@ -0,0 +1,59 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Quadrature encoder position tracking.
+
+use embassy_stm32::timer::qei::Qei;
+use embassy_stm32::timer::GeneralInstance4Channel;
+
+/// Quadrature encoder on a timer in encoder mode.
+///
+/// The timer counter is only 16 bits wide. `Encoder` extends it to an `i32`
+/// position that keeps counting across the wraparound, as long as
+/// [`position`](Self::position) is called at least once every 32768 counts.
+pub struct Encoder<'d, T: GeneralInstance4Channel> {
+    qei: Qei<'d, T>,
+    last_count: u16,
+    position: i32,
+}
+
+impl<'d, T: GeneralInstance4Channel> Encoder<'d, T> {
+    /// Create an encoder starting at position 0.
+    pub fn new(qei: Qei<'d, T>) -> Self {
+        let last_count = qei.count();
+        Self {
+            qei,
+            last_count,
+            position: 0,
+        }
+    }
+
+    /// Current position in counts (four per quadrature cycle).
+    pub fn position(&mut self) -> i32 {
+        let count = self.qei.count();
+        // The wrapping difference, read as signed, is the movement since the
+        // last call even if the counter overflowed in between
+        let delta = count.wrapping_sub(self.last_count) as i16;
+        self.last_count = count;
+        self.position = self.position.wrapping_add(delta as i32);
+        self.position
+    }
+
+    /// Make the current position the new zero.
+    pub fn reset(&mut self) {
+        self.last_count = self.qei.count();
+        self.position = 0;
+    }
+}
@@ -0,0 +1,87 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Traffic light finite state machine.
+//!
+//! Pure logic with no hardware access, so it can be unit tested on the host.
+
+/// State of a traffic light.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum LightState {
+    Red,
+    Green,
+    Yellow,
+}
+
+impl LightState {
+    /// State after this one: Red → Green → Yellow → Red.
+    pub fn next(self) -> Self {
+        match self {
+            LightState::Red => LightState::Green,
+            LightState::Green => LightState::Yellow,
+            LightState::Yellow => LightState::Red,
+        }
+    }
+
+    /// How long the light stays in this state, in milliseconds.
+    pub fn duration_ms(self) -> u64 {
+        match self {
+            LightState::Red => 5_000,
+            LightState::Green => 4_000,
+            LightState::Yellow => 1_500,
+        }
+    }
+
+    /// Which lamps are lit, as `[red, yellow, green]`.
+    pub fn lamps(self) -> [bool; 3] {
+        match self {
+            LightState::Red => [true, false, false],
+            LightState::Green => [false, false, true],
+            LightState::Yellow => [false, true, false],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LightState::*;
+
+    #[test]
+    fn transitions_follow_the_cycle() {
+        assert_eq!(Red.next(), Green);
+        assert_eq!(Green.next(), Yellow);
+        assert_eq!(Yellow.next(), Red);
+    }
+
+    #[test]
+    fn three_steps_return_to_start() {
+        for state in [Red, Green, Yellow] {
+            assert_eq!(state.next().next().next(), state);
+        }
+    }
+
+    #[test]
+    fn exactly_one_lamp_is_lit() {
+        for state in [Red, Green, Yellow] {
+            assert_eq!(state.lamps().iter().filter(|&&on| on).count(), 1);
+        }
+    }
+
+    #[test]
+    fn yellow_is_the_shortest_phase() {
+        assert!(Yellow.duration_ms() < Green.duration_ms());
+        assert!(Yellow.duration_ms() < Red.duration_ms());
+    }
+}
@@ -0,0 +1,65 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! HC-SR04 ultrasonic distance sensor.
+
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::Output;
+use embassy_time::{block_for, with_timeout, Duration, Instant};
+
+/// Longest echo the sensor produces for an obstacle in range (about 4 m).
+pub const MAX_ECHO: Duration = Duration::from_millis(25);
+
+// The sensor sends its ultrasonic burst before raising the echo line; it
+// normally does so within half a millisecond of the trigger
+const ECHO_START_TIMEOUT: Duration = Duration::from_millis(5);
+
+// Sound travels about 0.0343 cm/us; the echo covers the distance twice
+const US_PER_CM: f32 = 58.3;
+
+/// Convert an echo pulse width in microseconds to a distance in centimeters.
+pub fn echo_to_cm(echo_us: u64) -> f32 {
+    echo_us as f32 / US_PER_CM
+}
+
+/// HC-SR04 on a trigger output and an echo input.
+pub struct HcSr04<'d> {
+    trigger: Output<'d>,
+    echo: ExtiInput<'d>,
+}
+
+impl<'d> HcSr04<'d> {
+    /// Create a sensor; `trigger` should start low.
+    pub fn new(trigger: Output<'d>, echo: ExtiInput<'d>) -> Self {
+        Self { trigger, echo }
+    }
+
+    /// Measure the distance to the nearest obstacle in centimeters.
+    ///
+    /// Returns `None` if the sensor doesn't answer or nothing is in range,
+    /// instead of waiting forever for an echo that never comes. Leave at
+    /// least 60 ms between measurements so old echoes have died out.
+    pub async fn measure_cm(&mut self) -> Option<f32> {
+        // A 10 us pulse on the trigger starts a measurement
+        self.trigger.set_high();
+        block_for(Duration::from_micros(10));
+        self.trigger.set_low();
+
+        with_timeout(ECHO_START_TIMEOUT, self.echo.wait_for_high()).await.ok()?;
+        let start = Instant::now();
+        with_timeout(MAX_ECHO, self.echo.wait_for_low()).await.ok()?;
+        Some(echo_to_cm(start.elapsed().as_micros()))
+    }
+}
@@ -0,0 +1,52 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Background "I'm alive" LED blink.
+//!
+//! Spawn the task from any example, then change the rate at run time to
+//! signal activity:
+//!
+//! ```rust,ignore
+//! spawner.spawn(heartbeat_task(board.user_led.degrade(), Duration::from_millis(1000))).unwrap();
+//! // ...
+//! set_heartbeat_period(Duration::from_millis(200));
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_stm32::gpio::{AnyPin, Level, Output, Speed};
+use embassy_time::{Duration, Timer};
+
+static PERIOD_MS: AtomicU32 = AtomicU32::new(1000);
+
+/// Change the blink period of a running heartbeat.
+///
+/// The new period applies from the next LED toggle.
+pub fn set_heartbeat_period(period: Duration) {
+    PERIOD_MS.store(period.as_millis() as u32, Ordering::Relaxed);
+}
+
+/// Blink `led` with the given period (one on/off cycle).
+#[embassy_executor::task]
+pub async fn heartbeat_task(led: AnyPin, period: Duration) {
+    set_heartbeat_period(period);
+    let mut led = Output::new(led, Level::Low, Speed::Low);
+
+    loop {
+        led.toggle();
+        let half_period = PERIOD_MS.load(Ordering::Relaxed) / 2;
+        Timer::after_millis(half_period.max(1).into()).await;
+    }
+}
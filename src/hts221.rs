@@ -0,0 +1,167 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! HTS221 relative humidity and temperature sensor (X-NUCLEO-IKS01A2).
+
+use embedded_hal_async::i2c::I2c;
+
+/// 7-bit I2C address of the HTS221.
+pub const ADDRESS: u8 = 0x5F;
+/// Value of the `WHO_AM_I` register.
+pub const DEVICE_ID: u8 = 0xBC;
+
+const WHO_AM_I: u8 = 0x0F;
+const CTRL_REG1: u8 = 0x20;
+const STATUS_REG: u8 = 0x27;
+const HUMIDITY_OUT_L: u8 = 0x28;
+const CALIB_START: u8 = 0x30;
+// Setting the MSB of the register address enables auto-increment for
+// multi-byte reads
+const AUTO_INCREMENT: u8 = 0x80;
+
+// Power on, block data update, 1 Hz output data rate
+const CTRL_REG1_PD: u8 = 0x80;
+const CTRL_REG1_BDU: u8 = 0x04;
+const CTRL_REG1_ODR_1HZ: u8 = 0x01;
+
+/// HTS221 driver errors.
+#[derive(Debug, defmt::Format)]
+pub enum Error<E> {
+    /// The bus transfer failed.
+    I2c(E),
+    /// `WHO_AM_I` did not return [`DEVICE_ID`]; holds the value read.
+    WrongDevice(u8),
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Error::I2c(e)
+    }
+}
+
+/// One temperature and humidity measurement.
+#[derive(Clone, Copy, Debug, defmt::Format)]
+pub struct Measurement {
+    /// Temperature in °C.
+    pub temperature: f32,
+    /// Relative humidity in %.
+    pub humidity: f32,
+}
+
+/// Factory calibration points, stored in the sensor's registers 0x30..0x3F.
+///
+/// Each quantity is calibrated at two points: a raw ADC reading and the
+/// physical value it corresponds to. Readings are converted by linear
+/// interpolation between them.
+#[derive(Clone, Copy, Debug, defmt::Format)]
+struct Calibration {
+    h0_rh: f32,
+    h1_rh: f32,
+    h0_out: i16,
+    h1_out: i16,
+    t0_deg_c: f32,
+    t1_deg_c: f32,
+    t0_out: i16,
+    t1_out: i16,
+}
+
+impl Calibration {
+    fn from_registers(r: &[u8; 16]) -> Self {
+        // T0/T1 are 10-bit values: 8 bits in 0x32/0x33, the two MSBs of each in 0x35
+        let t0_x8 = u16::from(r[2]) | (u16::from(r[5] & 0x03) << 8);
+        let t1_x8 = u16::from(r[3]) | (u16::from(r[5] & 0x0C) << 6);
+        Self {
+            h0_rh: f32::from(r[0]) / 2.0,
+            h1_rh: f32::from(r[1]) / 2.0,
+            h0_out: i16::from_le_bytes([r[6], r[7]]),
+            h1_out: i16::from_le_bytes([r[10], r[11]]),
+            t0_deg_c: f32::from(t0_x8) / 8.0,
+            t1_deg_c: f32::from(t1_x8) / 8.0,
+            t0_out: i16::from_le_bytes([r[12], r[13]]),
+            t1_out: i16::from_le_bytes([r[14], r[15]]),
+        }
+    }
+
+    fn humidity(&self, raw: i16) -> f32 {
+        let h = interpolate(raw, self.h0_out, self.h1_out, self.h0_rh, self.h1_rh);
+        h.clamp(0.0, 100.0)
+    }
+
+    fn temperature(&self, raw: i16) -> f32 {
+        interpolate(raw, self.t0_out, self.t1_out, self.t0_deg_c, self.t1_deg_c)
+    }
+}
+
+fn interpolate(raw: i16, x0: i16, x1: i16, y0: f32, y1: f32) -> f32 {
+    let span = f32::from(x1) - f32::from(x0);
+    if span == 0.0 {
+        return y0;
+    }
+    y0 + (f32::from(raw) - f32::from(x0)) * (y1 - y0) / span
+}
+
+/// HTS221 on any async I2C bus (an `I2c` or a shared `I2cDevice`).
+pub struct Hts221<I> {
+    i2c: I,
+    calibration: Calibration,
+}
+
+impl<I: I2c> Hts221<I> {
+    /// Check the device ID, read the calibration and start 1 Hz conversions.
+    pub async fn new(mut i2c: I) -> Result<Self, Error<I::Error>> {
+        let mut id = [0u8; 1];
+        i2c.write_read(ADDRESS, &[WHO_AM_I], &mut id).await?;
+        if id[0] != DEVICE_ID {
+            return Err(Error::WrongDevice(id[0]));
+        }
+
+        let mut calib = [0u8; 16];
+        i2c.write_read(ADDRESS, &[CALIB_START | AUTO_INCREMENT], &mut calib)
+            .await?;
+        let calibration = Calibration::from_registers(&calib);
+
+        let ctrl = CTRL_REG1_PD | CTRL_REG1_BDU | CTRL_REG1_ODR_1HZ;
+        i2c.write(ADDRESS, &[CTRL_REG1, ctrl]).await?;
+
+        Ok(Self { i2c, calibration })
+    }
+
+    /// Whether both a new temperature and a new humidity sample are available.
+    pub async fn data_ready(&mut self) -> Result<bool, Error<I::Error>> {
+        let mut status = [0u8; 1];
+        self.i2c.write_read(ADDRESS, &[STATUS_REG], &mut status).await?;
+        Ok(status[0] & 0x03 == 0x03)
+    }
+
+    /// Read the latest sample and convert it with the calibration.
+    pub async fn read(&mut self) -> Result<Measurement, Error<I::Error>> {
+        // HUMIDITY_OUT_L/H followed by TEMP_OUT_L/H, all little-endian
+        let mut out = [0u8; 4];
+        self.i2c
+            .write_read(ADDRESS, &[HUMIDITY_OUT_L | AUTO_INCREMENT], &mut out)
+            .await?;
+        let humidity = i16::from_le_bytes([out[0], out[1]]);
+        let temperature = i16::from_le_bytes([out[2], out[3]]);
+        Ok(Measurement {
+            temperature: self.calibration.temperature(temperature),
+            humidity: self.calibration.humidity(humidity),
+        })
+    }
+
+    /// Release the bus.
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
@@ -0,0 +1,109 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Two-threshold switch to avoid chatter around a setpoint.
+
+/// On/off decision with hysteresis.
+///
+/// The output switches on when the value rises above `high` and off when it
+/// falls below `low`. Between the two thresholds it keeps its previous state,
+/// so noise on a value close to a single setpoint can't make it toggle
+/// rapidly ("chatter").
+#[derive(Clone, Copy, Debug)]
+pub struct Hysteresis {
+    low: f32,
+    high: f32,
+    on: bool,
+}
+
+impl Hysteresis {
+    /// Create a switch that starts off.
+    ///
+    /// The thresholds are swapped if `low` is greater than `high`.
+    pub fn new(low: f32, high: f32) -> Self {
+        Self {
+            low: low.min(high),
+            high: low.max(high),
+            on: false,
+        }
+    }
+
+    /// Feed a new value and return whether the output is on.
+    pub fn update(&mut self, value: f32) -> bool {
+        if value > self.high {
+            self.on = true;
+        } else if value < self.low {
+            self.on = false;
+        }
+        self.on
+    }
+
+    /// Current state of the output.
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_off() {
+        let mut switch = Hysteresis::new(20.0, 25.0);
+        assert!(!switch.is_on());
+        assert!(!switch.update(22.0));
+    }
+
+    #[test]
+    fn switches_at_the_thresholds() {
+        let mut switch = Hysteresis::new(20.0, 25.0);
+        assert!(!switch.update(25.0));
+        assert!(switch.update(25.1));
+        assert!(switch.update(20.0));
+        assert!(!switch.update(19.9));
+    }
+
+    #[test]
+    fn keeps_state_inside_the_band() {
+        let mut switch = Hysteresis::new(20.0, 25.0);
+        switch.update(30.0);
+        for value in [24.0, 21.0, 24.9, 20.1] {
+            assert!(switch.update(value));
+        }
+        switch.update(10.0);
+        for value in [20.1, 24.9, 22.0] {
+            assert!(!switch.update(value));
+        }
+    }
+
+    #[test]
+    fn noise_around_one_threshold_does_not_chatter() {
+        let mut switch = Hysteresis::new(20.0, 25.0);
+        switch.update(26.0);
+        // A single 25.0 setpoint would switch off at every dip below it
+        for value in [25.2, 24.8, 25.1, 24.9, 25.3] {
+            assert!(switch.update(value));
+        }
+    }
+
+    #[test]
+    fn swapped_thresholds() {
+        let mut switch = Hysteresis::new(25.0, 20.0);
+        assert!(switch.update(26.0));
+        assert!(switch.update(22.0));
+        assert!(!switch.update(19.0));
+    }
+}
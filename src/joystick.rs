@@ -0,0 +1,138 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Calibration and deadzone for analog joysticks.
+//!
+//! A thumb joystick is two potentiometers, one per axis. At rest each wiper
+//! sits near mid-scale, but rarely exactly there, and it jitters by a few
+//! counts. The center is therefore measured at startup, and a deadzone
+//! around it reads as exactly zero.
+
+/// Full-scale reading of the 12-bit ADC.
+pub const ADC_MAX: u16 = 4095;
+
+/// One calibrated joystick axis.
+#[derive(Clone, Copy, Debug)]
+pub struct Axis {
+    center: u16,
+    deadzone: f32,
+}
+
+impl Axis {
+    /// Create an axis that rests at the raw reading `center`.
+    ///
+    /// `deadzone` is the fraction of the travel around the center that reads
+    /// as zero, clamped to 0.0–0.9.
+    pub fn new(center: u16, deadzone: f32) -> Self {
+        Self {
+            center: center.min(ADC_MAX),
+            deadzone: deadzone.clamp(0.0, 0.9),
+        }
+    }
+
+    /// Raw reading the axis was calibrated to rest at.
+    pub fn center(&self) -> u16 {
+        self.center
+    }
+
+    /// Convert a raw reading to a position from -1.0 to 1.0.
+    ///
+    /// Each side of the center is scaled separately, so both ends reach
+    /// ±1.0 even with an off-center rest position. Outside the deadzone the
+    /// output starts again from zero, without a jump at its edge.
+    pub fn normalize(&self, raw: u16) -> f32 {
+        let offset = raw.min(ADC_MAX) as f32 - self.center as f32;
+        let half_range = if offset < 0.0 {
+            self.center
+        } else {
+            ADC_MAX - self.center
+        };
+        if half_range == 0 {
+            return 0.0;
+        }
+        let position = offset / half_range as f32;
+
+        let magnitude = position.abs();
+        if magnitude <= self.deadzone {
+            return 0.0;
+        }
+        let scaled = ((magnitude - self.deadzone) / (1.0 - self.deadzone)).min(1.0);
+        if position < 0.0 {
+            -scaled
+        } else {
+            scaled
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn center_and_ends() {
+        let axis = Axis::new(2048, 0.0);
+        assert_eq!(axis.normalize(2048), 0.0);
+        assert_eq!(axis.normalize(0), -1.0);
+        assert_eq!(axis.normalize(ADC_MAX), 1.0);
+    }
+
+    #[test]
+    fn off_center_rest_still_reaches_both_ends() {
+        let axis = Axis::new(1800, 0.0);
+        assert_eq!(axis.normalize(1800), 0.0);
+        assert_eq!(axis.normalize(0), -1.0);
+        assert_eq!(axis.normalize(ADC_MAX), 1.0);
+        assert_eq!(axis.normalize(900), -0.5);
+    }
+
+    #[test]
+    fn deadzone_reads_zero() {
+        let axis = Axis::new(2000, 0.1);
+        assert_eq!(axis.normalize(2000), 0.0);
+        assert_eq!(axis.normalize(2150), 0.0);
+        assert_eq!(axis.normalize(1850), 0.0);
+        assert!(axis.normalize(2300) > 0.0);
+        assert!(axis.normalize(1700) < 0.0);
+    }
+
+    #[test]
+    fn output_is_continuous_at_the_deadzone_edge() {
+        let axis = Axis::new(2000, 0.2);
+        // Just outside the deadzone (20% of 2095 counts) the output is close to zero
+        assert!(axis.normalize(2000 + 420) < 0.01);
+        assert_eq!(axis.normalize(ADC_MAX), 1.0);
+        assert_eq!(axis.normalize(0), -1.0);
+    }
+
+    #[test]
+    fn monotonic_over_the_full_range() {
+        let axis = Axis::new(2100, 0.05);
+        let mut previous = axis.normalize(0);
+        for raw in 1..=ADC_MAX {
+            let value = axis.normalize(raw);
+            assert!(value >= previous);
+            assert!((-1.0..=1.0).contains(&value));
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn center_at_the_rail_does_not_divide_by_zero() {
+        let axis = Axis::new(0, 0.1);
+        assert_eq!(axis.normalize(0), 0.0);
+        assert_eq!(axis.normalize(ADC_MAX), 1.0);
+    }
+}
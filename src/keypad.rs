@@ -0,0 +1,261 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 4x4 matrix keypad scanning.
+//!
+//! The keys sit at the crossings of 4 row and 4 column wires. Driving one
+//! row low at a time and reading which columns follow it tells which keys of
+//! that row are pressed.
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::gpio::{Input, OutputOpenDrain};
+#[cfg(target_arch = "arm")]
+use embassy_time::{block_for, Duration, Timer};
+
+/// Number of row wires.
+pub const ROWS: usize = 4;
+/// Number of column wires.
+pub const COLS: usize = 4;
+
+/// Characters of the common 4x4 membrane keypad, by row and column.
+pub const KEYMAP: [[char; COLS]; ROWS] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+/// Pressed keys of one scan: bit `c` of entry `r` is set when the key at
+/// row `r`, column `c` is down.
+pub type Scan = [u8; ROWS];
+
+/// The only pressed key of a scan as `(row, column)`, or `None` if no key
+/// or more than one key is down.
+pub fn single_key(scan: &Scan) -> Option<(usize, usize)> {
+    let mut found = None;
+    for (row, &bits) in scan.iter().enumerate() {
+        match bits.count_ones() {
+            0 => {}
+            1 if found.is_none() => found = Some((row, bits.trailing_zeros() as usize)),
+            _ => return None,
+        }
+    }
+    found
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterState {
+    /// All keys up, ready for a press.
+    Released,
+    /// One key down for `count` consecutive scans.
+    Pressing { key: (usize, usize), count: u8 },
+    /// A key was reported, or several were down: wait until all are up.
+    Locked,
+}
+
+/// Debounces scans and turns them into single key presses.
+///
+/// A key is reported once it has been the only key down for `stable_scans`
+/// consecutive scans, and only once per press. When several keys are down
+/// at the same time, nothing is reported until all are released. Ghost keys,
+/// which appear when three keys at the corners of a rectangle are pressed,
+/// can therefore never be reported.
+#[derive(Clone, Copy, Debug)]
+pub struct ScanFilter {
+    stable_scans: u8,
+    state: FilterState,
+}
+
+impl ScanFilter {
+    /// Create a filter requiring `stable_scans` identical scans (at least 1).
+    pub fn new(stable_scans: u8) -> Self {
+        Self {
+            stable_scans: stable_scans.max(1),
+            state: FilterState::Released,
+        }
+    }
+
+    /// Feed the next scan; returns the key as `(row, column)` when a press is
+    /// confirmed.
+    pub fn update(&mut self, scan: &Scan) -> Option<(usize, usize)> {
+        let released = scan.iter().all(|&bits| bits == 0);
+        match self.state {
+            FilterState::Locked => {
+                if released {
+                    self.state = FilterState::Released;
+                }
+                None
+            }
+            FilterState::Released | FilterState::Pressing { .. } => match single_key(scan) {
+                Some(key) => {
+                    let count = match self.state {
+                        FilterState::Pressing { key: previous, count } if previous == key => count + 1,
+                        _ => 1,
+                    };
+                    if count >= self.stable_scans {
+                        self.state = FilterState::Locked;
+                        Some(key)
+                    } else {
+                        self.state = FilterState::Pressing { key, count };
+                        None
+                    }
+                }
+                None if released => {
+                    self.state = FilterState::Released;
+                    None
+                }
+                None => {
+                    self.state = FilterState::Locked;
+                    None
+                }
+            },
+        }
+    }
+}
+
+/// Interval between two scans.
+#[cfg(target_arch = "arm")]
+pub const SCAN_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Keypad with open-drain row outputs and pulled-up column inputs.
+#[cfg(target_arch = "arm")]
+pub struct Keypad<'d> {
+    rows: [OutputOpenDrain<'d>; ROWS],
+    cols: [Input<'d>; COLS],
+    filter: ScanFilter,
+}
+
+#[cfg(target_arch = "arm")]
+impl<'d> Keypad<'d> {
+    /// Create a keypad; a key must be stable for 4 scans (20 ms) to count.
+    ///
+    /// The rows must start high (released) and the columns need pull-ups.
+    pub fn new(rows: [OutputOpenDrain<'d>; ROWS], cols: [Input<'d>; COLS]) -> Self {
+        Self {
+            rows,
+            cols,
+            filter: ScanFilter::new(4),
+        }
+    }
+
+    /// Read the state of all keys.
+    pub fn scan(&mut self) -> Scan {
+        let mut scan = [0; ROWS];
+        for (row, bits) in self.rows.iter_mut().zip(scan.iter_mut()) {
+            row.set_low();
+            // Let the column lines settle through the pull-ups
+            block_for(Duration::from_micros(10));
+            for (col, input) in self.cols.iter().enumerate() {
+                if input.is_low() {
+                    *bits |= 1 << col;
+                }
+            }
+            row.set_high();
+        }
+        scan
+    }
+
+    /// Wait for the next single key press and return its character.
+    pub async fn next_key(&mut self) -> char {
+        loop {
+            let scan = self.scan();
+            if let Some((row, col)) = self.filter.update(&scan) {
+                return KEYMAP[row][col];
+            }
+            Timer::after(SCAN_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: Scan = [0; ROWS];
+    // Key '6': row 1, column 2
+    const SIX: Scan = [0, 0b0100, 0, 0];
+    // Keys '6' and 'D'
+    const TWO_KEYS: Scan = [0, 0b0100, 0, 0b1000];
+
+    #[test]
+    fn single_key_positions() {
+        assert_eq!(single_key(&NONE), None);
+        assert_eq!(single_key(&SIX), Some((1, 2)));
+        assert_eq!(single_key(&[0, 0, 0, 0b1000]), Some((3, 3)));
+    }
+
+    #[test]
+    fn several_keys_are_not_a_single_key() {
+        assert_eq!(single_key(&TWO_KEYS), None);
+        assert_eq!(single_key(&[0b0011, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn keymap_matches_positions() {
+        let (row, col) = single_key(&SIX).unwrap();
+        assert_eq!(KEYMAP[row][col], '6');
+    }
+
+    #[test]
+    fn press_is_reported_once_after_stable_scans() {
+        let mut filter = ScanFilter::new(3);
+        assert_eq!(filter.update(&SIX), None);
+        assert_eq!(filter.update(&SIX), None);
+        assert_eq!(filter.update(&SIX), Some((1, 2)));
+        // Held down: no repeat
+        for _ in 0..10 {
+            assert_eq!(filter.update(&SIX), None);
+        }
+        // Released and pressed again
+        filter.update(&NONE);
+        filter.update(&SIX);
+        filter.update(&SIX);
+        assert_eq!(filter.update(&SIX), Some((1, 2)));
+    }
+
+    #[test]
+    fn bounces_restart_the_count() {
+        let mut filter = ScanFilter::new(3);
+        filter.update(&SIX);
+        filter.update(&SIX);
+        assert_eq!(filter.update(&NONE), None);
+        assert_eq!(filter.update(&SIX), None);
+        assert_eq!(filter.update(&SIX), None);
+        assert_eq!(filter.update(&SIX), Some((1, 2)));
+    }
+
+    #[test]
+    fn combinations_block_until_all_released() {
+        let mut filter = ScanFilter::new(2);
+        filter.update(&SIX);
+        assert_eq!(filter.update(&TWO_KEYS), None);
+        // Releasing one of the two keys does not report the other
+        for _ in 0..5 {
+            assert_eq!(filter.update(&SIX), None);
+        }
+        filter.update(&NONE);
+        filter.update(&SIX);
+        assert_eq!(filter.update(&SIX), Some((1, 2)));
+    }
+
+    #[test]
+    fn ghost_key_is_never_reported() {
+        // Three real keys in a rectangle make the fourth corner read as pressed
+        let ghosting: Scan = [0b0011, 0b0011, 0, 0];
+        let mut filter = ScanFilter::new(1);
+        assert_eq!(filter.update(&ghosting), None);
+        assert_eq!(filter.update(&[0b0001, 0, 0, 0]), None);
+    }
+}
@@ -0,0 +1,90 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Shared code for the NucleoF401RE getting-started examples.
+//!
+//! The binaries in `src/bin` pull board wiring and small reusable helpers
+//! from here, so each example can stay focused on the concept it teaches.
+//!
+//! Modules that touch the hardware are only built for the MCU. The others
+//! hold pure logic and also build on the host, where their unit tests run:
+//!
+//! ```text
+//! cargo test --lib --target x86_64-unknown-linux-gnu
+//! ```
+#![no_std]
+
+pub mod adc_filter;
+pub mod analog;
+pub mod battery;
+#[cfg(target_arch = "arm")]
+pub mod bsp;
+#[cfg(target_arch = "arm")]
+pub mod button;
+pub mod chaser;
+pub mod cli;
+#[cfg(target_arch = "arm")]
+pub mod clock;
+pub mod color;
+pub mod config_store;
+pub mod control;
+pub mod easing;
+#[cfg(target_arch = "arm")]
+pub mod encoder;
+pub mod fsm;
+#[cfg(target_arch = "arm")]
+pub mod hcsr04;
+#[cfg(target_arch = "arm")]
+pub mod heartbeat;
+pub mod hts221;
+pub mod hysteresis;
+pub mod joystick;
+pub mod keypad;
+#[cfg(target_arch = "arm")]
+pub mod logging;
+pub mod lsm6dsl;
+pub mod menu;
+pub mod morse;
+#[cfg(target_arch = "arm")]
+pub mod motor;
+#[cfg(target_arch = "arm")]
+pub mod music;
+#[cfg(target_arch = "arm")]
+pub mod profiling;
+pub mod ramp;
+#[cfg(target_arch = "arm")]
+pub mod recovery;
+pub mod reset;
+pub mod rng;
+#[cfg(target_arch = "arm")]
+pub mod safe_state;
+pub mod scheduler;
+pub mod seg7;
+#[cfg(target_arch = "arm")]
+pub mod serial;
+pub mod servo;
+#[cfg(target_arch = "arm")]
+pub mod soft_i2c;
+#[cfg(target_arch = "arm")]
+pub mod soft_pwm;
+pub mod stepper;
+pub mod supervisor;
+pub mod telemetry;
+#[cfg(target_arch = "arm")]
+pub mod timing;
+pub mod uid;
+pub mod waveform;
+#[cfg(target_arch = "arm")]
+pub mod ws2812;
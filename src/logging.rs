@@ -0,0 +1,99 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! defmt logging setup shared by the examples.
+//!
+//! The `log_*!` macros wrap the defmt ones and compile to nothing below the
+//! level chosen with a Cargo feature:
+//!
+//! | Feature | Logged |
+//! |---------|--------|
+//! | `log-trace` | everything |
+//! | `log-info` (or none) | info, warn, error |
+//! | `log-warn` | warn, error |
+//!
+//! If several are enabled, the most verbose wins. defmt applies its own
+//! `DEFMT_LOG` filter on top, set to `info` in `.cargo/config.toml`: trace
+//! and debug messages also need e.g. `DEFMT_LOG=trace` in the environment.
+
+use embassy_time::Instant;
+
+// Prefix every log line, including the panic message printed by panic_probe,
+// with the time since boot.
+defmt::timestamp!("{=u64:ms}", Instant::now().as_millis());
+
+/// Log level selected with the `log-*` features.
+#[cfg(feature = "log-trace")]
+pub const LEVEL: &str = "trace";
+/// Log level selected with the `log-*` features.
+#[cfg(all(not(feature = "log-trace"), any(feature = "log-info", not(feature = "log-warn"))))]
+pub const LEVEL: &str = "info";
+/// Log level selected with the `log-*` features.
+#[cfg(all(not(feature = "log-trace"), not(feature = "log-info"), feature = "log-warn"))]
+pub const LEVEL: &str = "warn";
+
+/// `defmt::trace!`, only with the `log-trace` feature.
+///
+/// Like the other `log_*!` macros, the arguments are not evaluated when the
+/// level is disabled.
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log-trace")]
+        ::defmt::trace!($($arg)*);
+    }};
+}
+
+/// `defmt::debug!`, only with the `log-trace` feature.
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log-trace")]
+        ::defmt::debug!($($arg)*);
+    }};
+}
+
+/// `defmt::info!`, unless only `log-warn` is enabled.
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {{
+        #[cfg(any(feature = "log-trace", feature = "log-info", not(feature = "log-warn")))]
+        ::defmt::info!($($arg)*);
+    }};
+}
+
+/// `defmt::warn!`, at every level.
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {{
+        ::defmt::warn!($($arg)*);
+    }};
+}
+
+/// `defmt::error!`, at every level.
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {{
+        ::defmt::error!($($arg)*);
+    }};
+}
+
+/// Set up defmt logging; call it first in `main`.
+///
+/// Binaries only link the timestamp above if they use this module. Until
+/// `embassy_stm32::init` starts the time driver, timestamps read zero.
+pub fn init_logging() {
+    defmt::info!("Logging started, timestamps are seconds since boot");
+}
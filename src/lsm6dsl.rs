@@ -0,0 +1,272 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! LSM6DSL accelerometer and gyroscope (X-NUCLEO-IKS01A2).
+
+use embedded_hal_async::i2c::I2c;
+
+/// 7-bit I2C address with the SA0 pin low.
+pub const ADDRESS_SA0_LOW: u8 = 0x6A;
+/// 7-bit I2C address with the SA0 pin high, as wired on the X-NUCLEO-IKS01A2.
+pub const ADDRESS_SA0_HIGH: u8 = 0x6B;
+/// Value of the `WHO_AM_I` register.
+pub const DEVICE_ID: u8 = 0x6A;
+
+const WHO_AM_I: u8 = 0x0F;
+const CTRL1_XL: u8 = 0x10;
+const CTRL2_G: u8 = 0x11;
+const CTRL3_C: u8 = 0x12;
+const STATUS_REG: u8 = 0x1E;
+const OUTX_L_G: u8 = 0x22;
+const OUTX_L_XL: u8 = 0x28;
+
+// Block data update, register address auto-increment
+const CTRL3_C_BDU: u8 = 0x40;
+const CTRL3_C_IF_INC: u8 = 0x04;
+
+const STATUS_XLDA: u8 = 0x01;
+const STATUS_GDA: u8 = 0x02;
+
+/// Accelerometer and gyroscope output data rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum OutputDataRate {
+    /// Sensor powered down.
+    PowerDown = 0b0000,
+    Hz12_5 = 0b0001,
+    Hz26 = 0b0010,
+    Hz52 = 0b0011,
+    Hz104 = 0b0100,
+    Hz208 = 0b0101,
+    Hz416 = 0b0110,
+    Hz833 = 0b0111,
+    Hz1660 = 0b1000,
+}
+
+/// Accelerometer full-scale range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum FullScale {
+    G2,
+    G4,
+    G8,
+    G16,
+}
+
+impl FullScale {
+    // Note the register encoding is not in ascending order
+    fn bits(self) -> u8 {
+        match self {
+            FullScale::G2 => 0b00,
+            FullScale::G4 => 0b10,
+            FullScale::G8 => 0b11,
+            FullScale::G16 => 0b01,
+        }
+    }
+
+    /// Sensitivity in g per count, from the datasheet.
+    pub fn g_per_count(self) -> f32 {
+        match self {
+            FullScale::G2 => 0.000_061,
+            FullScale::G4 => 0.000_122,
+            FullScale::G8 => 0.000_244,
+            FullScale::G16 => 0.000_488,
+        }
+    }
+}
+
+/// Gyroscope full-scale range, in degrees per second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum GyroFullScale {
+    Dps245,
+    Dps500,
+    Dps1000,
+    Dps2000,
+}
+
+impl GyroFullScale {
+    fn bits(self) -> u8 {
+        match self {
+            GyroFullScale::Dps245 => 0b00,
+            GyroFullScale::Dps500 => 0b01,
+            GyroFullScale::Dps1000 => 0b10,
+            GyroFullScale::Dps2000 => 0b11,
+        }
+    }
+
+    /// Sensitivity in degrees per second per count, from the datasheet.
+    pub fn dps_per_count(self) -> f32 {
+        match self {
+            GyroFullScale::Dps245 => 0.008_75,
+            GyroFullScale::Dps500 => 0.017_5,
+            GyroFullScale::Dps1000 => 0.035,
+            GyroFullScale::Dps2000 => 0.070,
+        }
+    }
+}
+
+/// LSM6DSL driver errors.
+#[derive(Debug, defmt::Format)]
+pub enum Error<E> {
+    /// The bus transfer failed.
+    I2c(E),
+    /// `WHO_AM_I` did not return [`DEVICE_ID`]; holds the value read.
+    WrongDevice(u8),
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Error::I2c(e)
+    }
+}
+
+/// Acceleration on the three axes, in g.
+#[derive(Clone, Copy, Debug, defmt::Format)]
+pub struct Acceleration {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Angular rate around the three axes, in degrees per second.
+///
+/// Positive values are counter-clockwise rotations when looking down the
+/// axis toward the origin (right-hand rule).
+#[derive(Clone, Copy, Debug, defmt::Format)]
+pub struct AngularRate {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// LSM6DSL on any async I2C bus (an `I2c` or a shared `I2cDevice`).
+///
+/// [`new`](Self::new) starts the accelerometer only; the gyroscope stays
+/// powered down until [`configure_gyroscope`](Self::configure_gyroscope).
+pub struct Lsm6dsl<I> {
+    i2c: I,
+    address: u8,
+    full_scale: FullScale,
+    gyro_full_scale: GyroFullScale,
+}
+
+impl<I: I2c> Lsm6dsl<I> {
+    /// Check the device ID and configure the accelerometer.
+    pub async fn new(
+        mut i2c: I,
+        address: u8,
+        odr: OutputDataRate,
+        full_scale: FullScale,
+    ) -> Result<Self, Error<I::Error>> {
+        let mut id = [0u8; 1];
+        i2c.write_read(address, &[WHO_AM_I], &mut id).await?;
+        if id[0] != DEVICE_ID {
+            return Err(Error::WrongDevice(id[0]));
+        }
+
+        i2c.write(address, &[CTRL3_C, CTRL3_C_BDU | CTRL3_C_IF_INC]).await?;
+        let mut sensor = Self {
+            i2c,
+            address,
+            full_scale,
+            gyro_full_scale: GyroFullScale::Dps245,
+        };
+        sensor.configure(odr, full_scale).await?;
+        Ok(sensor)
+    }
+
+    /// Change the output data rate and full-scale range.
+    pub async fn configure(&mut self, odr: OutputDataRate, full_scale: FullScale) -> Result<(), Error<I::Error>> {
+        let ctrl = ((odr as u8) << 4) | (full_scale.bits() << 2);
+        self.i2c.write(self.address, &[CTRL1_XL, ctrl]).await?;
+        self.full_scale = full_scale;
+        Ok(())
+    }
+
+    /// Change the gyroscope output data rate and full-scale range.
+    ///
+    /// [`OutputDataRate::PowerDown`] turns the gyroscope off again.
+    pub async fn configure_gyroscope(
+        &mut self,
+        odr: OutputDataRate,
+        full_scale: GyroFullScale,
+    ) -> Result<(), Error<I::Error>> {
+        let ctrl = ((odr as u8) << 4) | (full_scale.bits() << 2);
+        self.i2c.write(self.address, &[CTRL2_G, ctrl]).await?;
+        self.gyro_full_scale = full_scale;
+        Ok(())
+    }
+
+    /// Whether a new accelerometer sample is available.
+    pub async fn data_ready(&mut self) -> Result<bool, Error<I::Error>> {
+        let mut status = [0u8; 1];
+        self.i2c.write_read(self.address, &[STATUS_REG], &mut status).await?;
+        Ok(status[0] & STATUS_XLDA != 0)
+    }
+
+    /// Whether a new gyroscope sample is available.
+    pub async fn gyroscope_data_ready(&mut self) -> Result<bool, Error<I::Error>> {
+        let mut status = [0u8; 1];
+        self.i2c.write_read(self.address, &[STATUS_REG], &mut status).await?;
+        Ok(status[0] & STATUS_GDA != 0)
+    }
+
+    /// Raw accelerometer counts on X, Y and Z.
+    pub async fn read_raw(&mut self) -> Result<[i16; 3], Error<I::Error>> {
+        self.read_axes(OUTX_L_XL).await
+    }
+
+    /// Raw gyroscope counts on X, Y and Z.
+    pub async fn read_raw_gyroscope(&mut self) -> Result<[i16; 3], Error<I::Error>> {
+        self.read_axes(OUTX_L_G).await
+    }
+
+    async fn read_axes(&mut self, first_register: u8) -> Result<[i16; 3], Error<I::Error>> {
+        // OUTX_L, OUTX_H, OUTY_L, ... : each axis is a little-endian i16
+        let mut out = [0u8; 6];
+        self.i2c.write_read(self.address, &[first_register], &mut out).await?;
+        Ok([
+            i16::from_le_bytes([out[0], out[1]]),
+            i16::from_le_bytes([out[2], out[3]]),
+            i16::from_le_bytes([out[4], out[5]]),
+        ])
+    }
+
+    /// Acceleration in g, scaled for the configured full-scale range.
+    pub async fn read_acceleration(&mut self) -> Result<Acceleration, Error<I::Error>> {
+        let [x, y, z] = self.read_raw().await?;
+        let scale = self.full_scale.g_per_count();
+        Ok(Acceleration {
+            x: f32::from(x) * scale,
+            y: f32::from(y) * scale,
+            z: f32::from(z) * scale,
+        })
+    }
+
+    /// Angular rate in degrees per second, scaled for the configured
+    /// gyroscope full-scale range.
+    pub async fn read_angular_rate(&mut self) -> Result<AngularRate, Error<I::Error>> {
+        let [x, y, z] = self.read_raw_gyroscope().await?;
+        let scale = self.gyro_full_scale.dps_per_count();
+        Ok(AngularRate {
+            x: f32::from(x) * scale,
+            y: f32::from(y) * scale,
+            z: f32::from(z) * scale,
+        })
+    }
+
+    /// Release the bus.
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
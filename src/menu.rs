@@ -0,0 +1,182 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Scrollable list menu, independent of the display and input hardware.
+
+/// A list of items with one selected, shown through a window of a fixed
+/// number of rows.
+///
+/// Moving past either end wraps around. The window scrolls just enough to
+/// keep the selection visible.
+pub struct Menu<'a> {
+    items: &'a [&'a str],
+    rows: usize,
+    selected: usize,
+    top: usize,
+}
+
+/// One visible row of a [`Menu`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Row<'a> {
+    /// Position of the item in the whole list.
+    pub index: usize,
+    /// Text of the item.
+    pub label: &'a str,
+    /// Whether this is the selected item.
+    pub selected: bool,
+}
+
+impl<'a> Menu<'a> {
+    /// Create a menu showing `rows` items at a time, with the first selected.
+    pub fn new(items: &'a [&'a str], rows: usize) -> Self {
+        Self {
+            items,
+            rows: rows.max(1),
+            selected: 0,
+            top: 0,
+        }
+    }
+
+    /// Move the selection by `steps` items, down for positive values.
+    ///
+    /// Any number of steps is applied at once, so a fast turn of an encoder
+    /// read as a single large delta lands on the right item.
+    pub fn move_by(&mut self, steps: i32) {
+        if self.items.is_empty() {
+            return;
+        }
+        let len = self.items.len() as i64;
+        self.selected = (self.selected as i64 + i64::from(steps)).rem_euclid(len) as usize;
+
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + self.rows {
+            self.top = self.selected + 1 - self.rows;
+        }
+    }
+
+    /// Index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Text of the selected item, `None` if the menu is empty.
+    pub fn selected_label(&self) -> Option<&'a str> {
+        self.items.get(self.selected).copied()
+    }
+
+    /// The rows currently in the window, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = Row<'a>> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .skip(self.top)
+            .take(self.rows)
+            .map(move |(index, &label)| Row {
+                index,
+                label,
+                selected: index == self.selected,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: [&str; 5] = ["a", "b", "c", "d", "e"];
+
+    fn visible(menu: &Menu) -> [usize; 3] {
+        let mut indices = [usize::MAX; 3];
+        for (slot, row) in indices.iter_mut().zip(menu.rows()) {
+            *slot = row.index;
+        }
+        indices
+    }
+
+    #[test]
+    fn starts_at_the_first_item() {
+        let menu = Menu::new(&ITEMS, 3);
+        assert_eq!(menu.selected(), 0);
+        assert_eq!(menu.selected_label(), Some("a"));
+        assert_eq!(visible(&menu), [0, 1, 2]);
+    }
+
+    #[test]
+    fn wraps_around_both_ends() {
+        let mut menu = Menu::new(&ITEMS, 3);
+        menu.move_by(-1);
+        assert_eq!(menu.selected(), 4);
+        menu.move_by(1);
+        assert_eq!(menu.selected(), 0);
+    }
+
+    #[test]
+    fn large_steps_are_not_skipped() {
+        let mut menu = Menu::new(&ITEMS, 3);
+        menu.move_by(7);
+        assert_eq!(menu.selected(), 2);
+        menu.move_by(-13);
+        assert_eq!(menu.selected(), 4);
+    }
+
+    #[test]
+    fn window_follows_the_selection() {
+        let mut menu = Menu::new(&ITEMS, 3);
+        menu.move_by(2);
+        assert_eq!(visible(&menu), [0, 1, 2]);
+        menu.move_by(1);
+        assert_eq!(visible(&menu), [1, 2, 3]);
+        menu.move_by(1);
+        assert_eq!(visible(&menu), [2, 3, 4]);
+        menu.move_by(-2);
+        assert_eq!(visible(&menu), [2, 3, 4]);
+        menu.move_by(-1);
+        assert_eq!(visible(&menu), [1, 2, 3]);
+    }
+
+    #[test]
+    fn window_jumps_on_wrap() {
+        let mut menu = Menu::new(&ITEMS, 3);
+        menu.move_by(-1);
+        assert_eq!(visible(&menu), [2, 3, 4]);
+        menu.move_by(1);
+        assert_eq!(visible(&menu), [0, 1, 2]);
+    }
+
+    #[test]
+    fn only_the_selected_row_is_highlighted() {
+        let mut menu = Menu::new(&ITEMS, 3);
+        menu.move_by(1);
+        let highlighted: [bool; 3] = core::array::from_fn(|i| menu.rows().nth(i).unwrap().selected);
+        assert_eq!(highlighted, [false, true, false]);
+    }
+
+    #[test]
+    fn fewer_items_than_rows() {
+        let mut menu = Menu::new(&ITEMS[..2], 3);
+        assert_eq!(menu.rows().count(), 2);
+        menu.move_by(3);
+        assert_eq!(menu.selected(), 1);
+    }
+
+    #[test]
+    fn empty_menu() {
+        let mut menu = Menu::new(&[], 3);
+        menu.move_by(1);
+        assert_eq!(menu.selected_label(), None);
+        assert_eq!(menu.rows().count(), 0);
+    }
+}
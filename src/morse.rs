@@ -0,0 +1,237 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Morse code encoding and timing.
+//!
+//! All timing is expressed in units of one dot, following the international
+//! standard: a dash lasts three dots, the gap between elements of a letter
+//! one dot, between letters three dots and between words seven dots.
+
+/// Length of a dot, in dots.
+pub const DOT_UNITS: u32 = 1;
+/// Length of a dash, in dots.
+pub const DASH_UNITS: u32 = 3;
+/// Gap between the dots and dashes of one letter, in dots.
+pub const ELEMENT_GAP_UNITS: u32 = 1;
+/// Gap between two letters, in dots.
+pub const LETTER_GAP_UNITS: u32 = 3;
+/// Gap between two words, in dots.
+pub const WORD_GAP_UNITS: u32 = 7;
+
+/// Dot length in milliseconds for a speed in words per minute.
+///
+/// Uses the standard word "PARIS", which is 50 dots long.
+pub const fn dot_ms(words_per_minute: u32) -> u32 {
+    60_000 / (50 * words_per_minute)
+}
+
+/// Morse code of a character as dots (`.`) and dashes (`-`).
+///
+/// Letters are case-insensitive. Returns `None` for characters without a
+/// Morse code, including whitespace.
+pub fn encode(c: char) -> Option<&'static str> {
+    let code = match c.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        '.' => ".-.-.-",
+        ',' => "--..--",
+        '?' => "..--..",
+        '/' => "-..-.",
+        '=' => "-...-",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// One step of a transmission: the output level and how long to hold it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub struct Pulse {
+    /// Whether the LED (or tone) is on.
+    pub on: bool,
+    /// Duration in dots.
+    pub units: u32,
+}
+
+/// Iterator over the pulses that transmit a text.
+///
+/// Characters without a Morse code are skipped, runs of whitespace become a
+/// single word gap. There is no gap before the first or after the last
+/// letter, so the caller decides how long to pause between repetitions.
+pub struct Pulses<'a> {
+    chars: core::str::Chars<'a>,
+    code: &'static [u8],
+    gap: u32,
+    started: bool,
+    new_word: bool,
+}
+
+impl<'a> Pulses<'a> {
+    /// Start transmitting `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars(),
+            code: &[],
+            gap: 0,
+            started: false,
+            new_word: false,
+        }
+    }
+}
+
+impl Iterator for Pulses<'_> {
+    type Item = Pulse;
+
+    fn next(&mut self) -> Option<Pulse> {
+        loop {
+            if let Some((&element, rest)) = self.code.split_first() {
+                if self.gap > 0 {
+                    let units = core::mem::take(&mut self.gap);
+                    return Some(Pulse { on: false, units });
+                }
+                self.code = rest;
+                if !rest.is_empty() {
+                    self.gap = ELEMENT_GAP_UNITS;
+                }
+                let units = if element == b'-' { DASH_UNITS } else { DOT_UNITS };
+                return Some(Pulse { on: true, units });
+            }
+
+            let c = self.chars.next()?;
+            if c.is_whitespace() {
+                self.new_word = self.started;
+            } else if let Some(code) = encode(c) {
+                if self.started {
+                    self.gap = if self.new_word {
+                        WORD_GAP_UNITS
+                    } else {
+                        LETTER_GAP_UNITS
+                    };
+                }
+                self.code = code.as_bytes();
+                self.started = true;
+                self.new_word = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(units: u32) -> Pulse {
+        Pulse { on: true, units }
+    }
+
+    fn off(units: u32) -> Pulse {
+        Pulse { on: false, units }
+    }
+
+    fn total_units(text: &str) -> u32 {
+        Pulses::new(text).map(|pulse| pulse.units).sum()
+    }
+
+    #[test]
+    fn encodes_letters_and_digits() {
+        assert_eq!(encode('S'), Some("..."));
+        assert_eq!(encode('O'), Some("---"));
+        assert_eq!(encode('q'), Some("--.-"));
+        assert_eq!(encode('0'), Some("-----"));
+        assert_eq!(encode('7'), Some("--..."));
+    }
+
+    #[test]
+    fn unsupported_characters_have_no_code() {
+        assert_eq!(encode(' '), None);
+        assert_eq!(encode('#'), None);
+        assert_eq!(encode('é'), None);
+    }
+
+    #[test]
+    fn all_codes_use_only_dots_and_dashes() {
+        for c in ('A'..='Z').chain('0'..='9') {
+            let code = encode(c).unwrap();
+            assert!(!code.is_empty());
+            assert!(code.bytes().all(|b| b == b'.' || b == b'-'));
+        }
+    }
+
+    #[test]
+    fn elements_of_a_letter_are_one_dot_apart() {
+        let mut pulses = Pulses::new("A");
+        assert_eq!(pulses.next(), Some(on(1)));
+        assert_eq!(pulses.next(), Some(off(1)));
+        assert_eq!(pulses.next(), Some(on(3)));
+        assert_eq!(pulses.next(), None);
+    }
+
+    #[test]
+    fn letter_and_word_gaps() {
+        let mut pulses = Pulses::new("EE E");
+        assert_eq!(pulses.next(), Some(on(1)));
+        assert_eq!(pulses.next(), Some(off(3)));
+        assert_eq!(pulses.next(), Some(on(1)));
+        assert_eq!(pulses.next(), Some(off(7)));
+        assert_eq!(pulses.next(), Some(on(1)));
+        assert_eq!(pulses.next(), None);
+    }
+
+    #[test]
+    fn paris_is_fifty_dots_with_its_word_gap() {
+        assert_eq!(total_units("PARIS") + WORD_GAP_UNITS, 50);
+        assert_eq!(dot_ms(20), 60);
+    }
+
+    #[test]
+    fn skips_unsupported_characters_and_extra_spaces() {
+        assert_eq!(total_units("S#O"), total_units("SO"));
+        assert_eq!(total_units("  SOS   SOS "), total_units("SOS SOS"));
+        assert_eq!(Pulses::new("#~").next(), None);
+    }
+}
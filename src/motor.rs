@@ -0,0 +1,65 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! DC motor driven through an H-bridge.
+
+use embassy_stm32::timer::simple_pwm::SimplePwmChannel;
+use embassy_stm32::timer::GeneralInstance4Channel;
+
+/// DC motor on an H-bridge with two PWM inputs (L298N, L9110S, DRV8833, ...).
+///
+/// Driving `in1` moves the motor forward, driving `in2` moves it in reverse.
+/// The two channels may belong to different timers.
+pub struct HBridgeMotor<'d, A: GeneralInstance4Channel, B: GeneralInstance4Channel> {
+    in1: SimplePwmChannel<'d, A>,
+    in2: SimplePwmChannel<'d, B>,
+}
+
+impl<'d, A: GeneralInstance4Channel, B: GeneralInstance4Channel> HBridgeMotor<'d, A, B> {
+    /// Create a motor on the bridge inputs `in1` and `in2`, initially coasting.
+    pub fn new(mut in1: SimplePwmChannel<'d, A>, mut in2: SimplePwmChannel<'d, B>) -> Self {
+        in1.set_duty_cycle_fully_off();
+        in2.set_duty_cycle_fully_off();
+        in1.enable();
+        in2.enable();
+        Self { in1, in2 }
+    }
+
+    /// Set the speed in percent, from -100 (full reverse) to 100 (full forward).
+    ///
+    /// Values outside this range are clamped; 0 lets the motor coast.
+    pub fn set_speed(&mut self, speed: i8) {
+        let duty = speed.unsigned_abs().min(100);
+        if speed >= 0 {
+            self.in2.set_duty_cycle_fully_off();
+            self.in1.set_duty_cycle_percent(duty);
+        } else {
+            self.in1.set_duty_cycle_fully_off();
+            self.in2.set_duty_cycle_percent(duty);
+        }
+    }
+
+    /// Release both motor terminals, letting the motor spin down freely.
+    pub fn coast(&mut self) {
+        self.in1.set_duty_cycle_fully_off();
+        self.in2.set_duty_cycle_fully_off();
+    }
+
+    /// Short the motor terminals, stopping it quickly.
+    pub fn brake(&mut self) {
+        self.in1.set_duty_cycle_fully_on();
+        self.in2.set_duty_cycle_fully_on();
+    }
+}
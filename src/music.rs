@@ -0,0 +1,76 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Tones and melodies on a passive buzzer.
+
+use embassy_stm32::time::hz;
+use embassy_stm32::timer::simple_pwm::SimplePwm;
+use embassy_stm32::timer::{Channel, GeneralInstance4Channel};
+use embassy_time::{Duration, Timer};
+
+/// Note frequencies in Hz, equal temperament with A4 = 440 Hz.
+pub mod notes {
+    /// Silence.
+    pub const REST: u32 = 0;
+    pub const C4: u32 = 262;
+    pub const CS4: u32 = 277;
+    pub const D4: u32 = 294;
+    pub const DS4: u32 = 311;
+    pub const E4: u32 = 330;
+    pub const F4: u32 = 349;
+    pub const FS4: u32 = 370;
+    pub const G4: u32 = 392;
+    pub const GS4: u32 = 415;
+    pub const A4: u32 = 440;
+    pub const AS4: u32 = 466;
+    pub const B4: u32 = 494;
+    pub const C5: u32 = 523;
+    pub const CS5: u32 = 554;
+    pub const D5: u32 = 587;
+    pub const DS5: u32 = 622;
+    pub const E5: u32 = 659;
+    pub const F5: u32 = 698;
+    pub const FS5: u32 = 740;
+    pub const G5: u32 = 784;
+    pub const GS5: u32 = 831;
+    pub const A5: u32 = 880;
+    pub const AS5: u32 = 932;
+    pub const B5: u32 = 988;
+    pub const C6: u32 = 1047;
+}
+
+/// Play `frequency_hz` on `channel` for `duration`, then silence it.
+///
+/// A frequency of [`notes::REST`] keeps the channel disabled for `duration`.
+/// The duty cycle is set to 50%, which gives a passive buzzer the loudest tone.
+pub async fn play_tone<T: GeneralInstance4Channel>(
+    pwm: &mut SimplePwm<'_, T>,
+    channel: Channel,
+    frequency_hz: u32,
+    duration: Duration,
+) {
+    if frequency_hz == notes::REST {
+        pwm.channel(channel).disable();
+    } else {
+        // Changing the frequency changes the max duty, so the duty cycle
+        // must be set again afterwards
+        pwm.set_frequency(hz(frequency_hz));
+        let mut ch = pwm.channel(channel);
+        ch.set_duty_cycle_fraction(1, 2);
+        ch.enable();
+    }
+    Timer::after(duration).await;
+    pwm.channel(channel).disable();
+}
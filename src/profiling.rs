@@ -0,0 +1,35 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Timing code blocks with `embassy_time::Instant`.
+
+/// Evaluate an expression, log how long it took, and return its value.
+///
+/// The label must be a string. The duration comes from the embassy time base,
+/// whose resolution is one tick (about 30 us at the 32.768 kHz tick rate):
+/// to time shorter code, run it many times in a loop and time the loop.
+///
+/// ```ignore
+/// let sample = time_it!("ADC read", adc.blocking_read(&mut pin));
+/// ```
+#[macro_export]
+macro_rules! time_it {
+    ($label:expr, $e:expr) => {{
+        let start = ::embassy_time::Instant::now();
+        let result = $e;
+        ::defmt::info!("{=str}: {=u64} us", $label, start.elapsed().as_micros());
+        result
+    }};
+}
@@ -0,0 +1,152 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Rate-limited setpoint changes.
+
+/// Moves a value toward a target by at most a fixed amount per step.
+///
+/// Called at a fixed rate, e.g. from a `Ticker`, this turns an abrupt
+/// change of setpoint into a linear ramp: with a step of 1.0 every 20 ms, a
+/// change from 0 to 100 takes 2 s. Useful for motor soft starts, fades and
+/// anything else that should not jump.
+#[derive(Clone, Copy, Debug)]
+pub struct Ramp {
+    value: f32,
+    target: f32,
+    max_step: f32,
+}
+
+impl Ramp {
+    /// Create a ramp at 0.0 that changes by at most `max_step` per step.
+    ///
+    /// A negative `max_step` is treated as positive.
+    pub fn new(max_step: f32) -> Self {
+        Self {
+            value: 0.0,
+            target: 0.0,
+            max_step: max_step.abs(),
+        }
+    }
+
+    /// Set the value to reach; the ramp gets there over the next steps.
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// The value the ramp is heading to.
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// The current output.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Whether the output has reached the target.
+    pub fn is_settled(&self) -> bool {
+        self.value == self.target
+    }
+
+    /// Jump straight to `value`, e.g. for an emergency stop.
+    pub fn reset(&mut self, value: f32) {
+        self.value = value;
+        self.target = value;
+    }
+
+    /// Move one step toward the target and return the new output.
+    ///
+    /// The last step lands exactly on the target, without overshooting it.
+    pub fn step(&mut self) -> f32 {
+        let remaining = self.target - self.value;
+        self.value = if remaining.abs() <= self.max_step {
+            self.target
+        } else if remaining > 0.0 {
+            self.value + self.max_step
+        } else {
+            self.value - self.max_step
+        };
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_at_zero_and_settled() {
+        let ramp = Ramp::new(1.0);
+        assert_eq!(ramp.value(), 0.0);
+        assert!(ramp.is_settled());
+    }
+
+    #[test]
+    fn ramps_up_at_the_max_rate() {
+        let mut ramp = Ramp::new(10.0);
+        ramp.set_target(35.0);
+        assert_eq!(ramp.step(), 10.0);
+        assert_eq!(ramp.step(), 20.0);
+        assert_eq!(ramp.step(), 30.0);
+        assert!(!ramp.is_settled());
+        // The last step is shorter and lands on the target
+        assert_eq!(ramp.step(), 35.0);
+        assert!(ramp.is_settled());
+        assert_eq!(ramp.step(), 35.0);
+    }
+
+    #[test]
+    fn ramps_down_and_through_zero() {
+        let mut ramp = Ramp::new(25.0);
+        ramp.reset(50.0);
+        ramp.set_target(-50.0);
+        let mut steps = 0;
+        while !ramp.is_settled() {
+            ramp.step();
+            steps += 1;
+        }
+        assert_eq!(steps, 4);
+        assert_eq!(ramp.value(), -50.0);
+    }
+
+    #[test]
+    fn new_target_mid_ramp() {
+        let mut ramp = Ramp::new(10.0);
+        ramp.set_target(100.0);
+        ramp.step();
+        ramp.step();
+        ramp.set_target(0.0);
+        assert_eq!(ramp.step(), 10.0);
+        assert_eq!(ramp.step(), 0.0);
+    }
+
+    #[test]
+    fn reset_jumps_immediately() {
+        let mut ramp = Ramp::new(1.0);
+        ramp.set_target(80.0);
+        ramp.step();
+        ramp.reset(0.0);
+        assert_eq!(ramp.value(), 0.0);
+        assert_eq!(ramp.target(), 0.0);
+        assert!(ramp.is_settled());
+    }
+
+    #[test]
+    fn negative_step_is_treated_as_positive() {
+        let mut ramp = Ramp::new(-5.0);
+        ramp.set_target(10.0);
+        assert_eq!(ramp.step(), 5.0);
+    }
+}
@@ -0,0 +1,108 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! I2C bus recovery.
+//!
+//! An I2C device drives SDA low whenever it sends a 0 bit or an ACK, and
+//! lets go only on the next SCL clock. If the master stops clocking in the
+//! middle of a transfer, the device keeps holding SDA low and waits forever
+//! for the rest of the byte. The master then sees a busy bus: it can't
+//! send a start condition, and every transfer fails with a bus error or a
+//! timeout. This happens when:
+//!
+//! - the MCU is reset or reflashed during a transfer, while the sensor keeps
+//!   its power and its state;
+//! - noise on a long cable adds or swallows a clock edge, so master and
+//!   device disagree about where in the byte they are;
+//! - a transfer is aborted halfway, e.g. by a timeout.
+//!
+//! Resetting the I2C peripheral doesn't help, as the device is the one
+//! holding the bus. The standard remedy (bus clear, in the I2C
+//! specification) is to clock SCL by hand until the device has shifted out
+//! the rest of its byte and releases SDA, at most 9 clocks (8 data bits and
+//! the ACK), and then send a stop condition, which resets the bus logic of
+//! every device.
+
+use embassy_stm32::gpio::{Level, OutputOpenDrain, Pin, Speed};
+use embassy_stm32::Peripheral;
+
+use crate::timing::delay_us;
+
+/// Most SCL clocks a device can need to release SDA.
+pub const MAX_CLOCK_PULSES: u8 = 9;
+
+// Half an SCL period. The delay resolution of one timer tick makes the
+// clock slower than 100 kHz, which every device accepts.
+const HALF_PERIOD_US: u32 = 5;
+
+/// Bus recovery errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// SCL is held low: a device is stuck stretching the clock, or the line
+    /// is shorted to GND. Only a power cycle of the device can help.
+    SclStuckLow,
+    /// SDA was still low after [`MAX_CLOCK_PULSES`] clocks: the line is
+    /// shorted to GND or the device is not responding at all.
+    SdaStuckLow,
+}
+
+/// Free an I2C bus whose SDA line is held low by a device.
+///
+/// Takes the two bus pins as open-drain GPIOs, so the I2C peripheral must
+/// not own them: drop the `I2c` driver first (build it from `&mut` pins to
+/// get them back) and create it again afterwards, which also resets the
+/// peripheral.
+///
+/// Returns the number of clocks it took for SDA to be released, 0 if the
+/// bus was already free. The lines need external pull-ups, as for I2C.
+pub fn recover_i2c_bus(scl: impl Peripheral<P = impl Pin>, sda: impl Peripheral<P = impl Pin>) -> Result<u8, Error> {
+    let mut scl = OutputOpenDrain::new(scl, Level::High, Speed::Low);
+    let mut sda = OutputOpenDrain::new(sda, Level::High, Speed::Low);
+    delay_us(HALF_PERIOD_US);
+
+    if scl.is_low() {
+        return Err(Error::SclStuckLow);
+    }
+    if sda.is_high() {
+        return Ok(0);
+    }
+
+    // Each clock makes the device shift out one more bit; it releases SDA
+    // at the latest when the byte and its ACK slot are done
+    let mut pulses = 0;
+    while sda.is_low() {
+        if pulses == MAX_CLOCK_PULSES {
+            return Err(Error::SdaStuckLow);
+        }
+        scl.set_low();
+        delay_us(HALF_PERIOD_US);
+        scl.set_high();
+        delay_us(HALF_PERIOD_US);
+        pulses += 1;
+    }
+
+    // Stop condition: SDA rises while SCL is high. Every device on the bus
+    // goes back to waiting for a start condition.
+    scl.set_low();
+    delay_us(HALF_PERIOD_US);
+    sda.set_low();
+    delay_us(HALF_PERIOD_US);
+    scl.set_high();
+    delay_us(HALF_PERIOD_US);
+    sda.set_high();
+    delay_us(HALF_PERIOD_US);
+
+    Ok(pulses)
+}
@@ -0,0 +1,130 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Cause of the last reset.
+//!
+//! The RCC latches a flag for each reset source in its CSR register. The
+//! flags survive the reset and stay set until cleared by software, so they
+//! must be read and cleared once at startup to tell the causes apart.
+
+// Reset flags in RCC_CSR (RM0368, "RCC clock control & status register")
+const RMVF: u32 = 1 << 24;
+const BORRSTF: u32 = 1 << 25;
+const PINRSTF: u32 = 1 << 26;
+const PORRSTF: u32 = 1 << 27;
+const SFTRSTF: u32 = 1 << 28;
+const IWDGRSTF: u32 = 1 << 29;
+const WWDGRSTF: u32 = 1 << 30;
+const LPWRRSTF: u32 = 1 << 31;
+
+/// Why the MCU restarted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum ResetCause {
+    /// Power was applied.
+    PowerOn,
+    /// The supply dropped below the brownout threshold.
+    Brownout,
+    /// The NRST pin was pulled low, e.g. by the reset button or the debugger.
+    Pin,
+    /// The firmware requested a reset (`SCB::sys_reset`).
+    Software,
+    /// The independent watchdog (IWDG) expired.
+    IndependentWatchdog,
+    /// The window watchdog (WWDG) expired or was refreshed too early.
+    WindowWatchdog,
+    /// Entering Standby or Stop mode was not allowed by the option bytes.
+    LowPower,
+    /// No flag set, e.g. because they were already cleared.
+    Unknown,
+}
+
+impl ResetCause {
+    /// Decode the reset flags of an RCC_CSR value.
+    ///
+    /// Every internal reset also drives the NRST pin low, and a power-on
+    /// also sets the brownout flag, so several flags are usually set at
+    /// once. The most specific one wins.
+    pub fn from_csr(csr: u32) -> Self {
+        if csr & LPWRRSTF != 0 {
+            ResetCause::LowPower
+        } else if csr & WWDGRSTF != 0 {
+            ResetCause::WindowWatchdog
+        } else if csr & IWDGRSTF != 0 {
+            ResetCause::IndependentWatchdog
+        } else if csr & SFTRSTF != 0 {
+            ResetCause::Software
+        } else if csr & PORRSTF != 0 {
+            ResetCause::PowerOn
+        } else if csr & BORRSTF != 0 {
+            ResetCause::Brownout
+        } else if csr & PINRSTF != 0 {
+            ResetCause::Pin
+        } else {
+            ResetCause::Unknown
+        }
+    }
+}
+
+/// Read the cause of the last reset and clear the flags.
+///
+/// Call it once, early at startup: a second call returns
+/// [`ResetCause::Unknown`].
+#[cfg(target_arch = "arm")]
+pub fn read_and_clear() -> ResetCause {
+    use embassy_stm32::pac;
+
+    let cause = ResetCause::from_csr(pac::RCC.csr().read().0);
+    pac::RCC.csr().modify(|w| w.0 |= RMVF);
+    cause
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_flags() {
+        assert_eq!(ResetCause::from_csr(PINRSTF), ResetCause::Pin);
+        assert_eq!(ResetCause::from_csr(BORRSTF), ResetCause::Brownout);
+        assert_eq!(ResetCause::from_csr(0), ResetCause::Unknown);
+    }
+
+    #[test]
+    fn power_on_sets_pin_and_brownout_too() {
+        assert_eq!(ResetCause::from_csr(PORRSTF | BORRSTF | PINRSTF), ResetCause::PowerOn);
+        assert_eq!(ResetCause::from_csr(BORRSTF | PINRSTF), ResetCause::Brownout);
+    }
+
+    #[test]
+    fn internal_resets_also_set_pin_flag() {
+        assert_eq!(ResetCause::from_csr(SFTRSTF | PINRSTF), ResetCause::Software);
+        assert_eq!(
+            ResetCause::from_csr(IWDGRSTF | PINRSTF),
+            ResetCause::IndependentWatchdog
+        );
+        assert_eq!(ResetCause::from_csr(WWDGRSTF | PINRSTF), ResetCause::WindowWatchdog);
+        assert_eq!(ResetCause::from_csr(LPWRRSTF | PINRSTF), ResetCause::LowPower);
+    }
+
+    #[test]
+    fn other_csr_bits_are_ignored() {
+        // LSION, LSIRDY and RMVF
+        assert_eq!(ResetCause::from_csr(0b11 | RMVF), ResetCause::Unknown);
+        assert_eq!(
+            ResetCause::from_csr(0b11 | IWDGRSTF | PINRSTF),
+            ResetCause::IndependentWatchdog
+        );
+    }
+}
@@ -0,0 +1,98 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Driving critical outputs to a safe state when the program panics.
+
+use core::cell::RefCell;
+
+use embassy_stm32::gpio::Output;
+use embassy_stm32::timer::GeneralInstance4Channel;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+
+use crate::motor::HBridgeMotor;
+
+/// An output with a safe state, such as a stopped motor or a switched-off heater.
+pub trait SafeOff {
+    /// Put the output in its safe state. Must not panic.
+    fn safe_off(&mut self);
+}
+
+impl SafeOff for Output<'_> {
+    fn safe_off(&mut self) {
+        self.set_low();
+    }
+}
+
+impl<A: GeneralInstance4Channel, B: GeneralInstance4Channel> SafeOff for HBridgeMotor<'_, A, B> {
+    fn safe_off(&mut self) {
+        self.coast();
+    }
+}
+
+/// Static home for an output that the panic handler must be able to reach.
+///
+/// A panic handler receives no arguments, so it can't use values owned by
+/// `main` or by a task. Moving the output into a `static SafeStateGuard`
+/// makes it reachable from both the application, through [`with`](Self::with),
+/// and the panic handler, through [`make_safe`](Self::make_safe).
+pub struct SafeStateGuard<T> {
+    inner: Mutex<CriticalSectionRawMutex, RefCell<Option<T>>>,
+}
+
+impl<T: SafeOff> SafeStateGuard<T> {
+    /// Create an empty guard, usable in a `static`.
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Hand the output over to the guard.
+    pub fn install(&self, output: T) {
+        self.inner.lock(|cell| *cell.borrow_mut() = Some(output));
+    }
+
+    /// Use the output; returns `None` if none is installed.
+    ///
+    /// `f` runs inside a critical section, so it should be short.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.inner.lock(|cell| cell.borrow_mut().as_mut().map(f))
+    }
+
+    /// Put the output in its safe state; meant to be called from a panic handler.
+    ///
+    /// Returns `false` if no output is installed, or if the panic happened
+    /// inside [`with`](Self::with) while the output was borrowed. The handler
+    /// then has to fall back to another way, such as resetting the MCU.
+    pub fn make_safe(&self) -> bool {
+        self.inner.lock(|cell| match cell.try_borrow_mut() {
+            Ok(mut output) => match output.as_mut() {
+                Some(output) => {
+                    output.safe_off();
+                    true
+                }
+                None => false,
+            },
+            Err(_) => false,
+        })
+    }
+}
+
+impl<T: SafeOff> Default for SafeStateGuard<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
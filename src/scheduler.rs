@@ -0,0 +1,124 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Cooperative scheduling of periodic jobs from a single task.
+//!
+//! Instead of spawning one task per periodic job, one loop wakes up at a
+//! fixed tick and asks each job whether it is due. The jobs run one after
+//! the other, so they can share data without any synchronization.
+
+/// A job that runs every `period_ms` milliseconds.
+///
+/// Times are `u32` milliseconds compared with wrapping arithmetic, which
+/// stays correct across the wraparound after 49 days. The job is due at the
+/// first check, then every period after that. Get the current time with
+/// `timing::now_ms`.
+#[derive(Clone, Copy, Debug)]
+pub struct PeriodicJob {
+    period_ms: u32,
+    next_ms: Option<u32>,
+}
+
+impl PeriodicJob {
+    /// Create a job that runs every `period_ms` milliseconds (at least 1).
+    pub const fn new(period_ms: u32) -> Self {
+        Self {
+            period_ms: if period_ms == 0 { 1 } else { period_ms },
+            next_ms: None,
+        }
+    }
+
+    /// Period of the job, in milliseconds.
+    pub fn period_ms(&self) -> u32 {
+        self.period_ms
+    }
+
+    /// Return whether the job is due at `now_ms`, and if so schedule the next run.
+    ///
+    /// The next run is one period after the scheduled time, not after
+    /// `now_ms`, so checking a little late does not make the job drift. If
+    /// a whole period or more was missed, the missed runs are skipped
+    /// rather than run in a burst.
+    pub fn should_run(&mut self, now_ms: u32) -> bool {
+        let next = match self.next_ms {
+            None => now_ms,
+            // Negative when `next` is still in the future
+            Some(next) if (now_ms.wrapping_sub(next) as i32) < 0 => return false,
+            Some(next) => next,
+        };
+        let mut following = next.wrapping_add(self.period_ms);
+        if (now_ms.wrapping_sub(following) as i32) >= 0 {
+            following = now_ms.wrapping_add(self.period_ms);
+        }
+        self.next_ms = Some(following);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_at_first_check() {
+        let mut job = PeriodicJob::new(100);
+        assert!(job.should_run(1_234));
+        assert!(!job.should_run(1_234));
+    }
+
+    #[test]
+    fn runs_once_per_period() {
+        let mut job = PeriodicJob::new(100);
+        let runs = (0..1_000).step_by(10).filter(|&now| job.should_run(now)).count();
+        assert_eq!(runs, 10);
+    }
+
+    #[test]
+    fn late_checks_do_not_drift() {
+        let mut job = PeriodicJob::new(100);
+        assert!(job.should_run(0));
+        assert!(job.should_run(130));
+        // Still scheduled at 200, not at 230
+        assert!(!job.should_run(199));
+        assert!(job.should_run(200));
+    }
+
+    #[test]
+    fn missed_runs_are_skipped() {
+        let mut job = PeriodicJob::new(100);
+        assert!(job.should_run(0));
+        assert!(job.should_run(550));
+        assert!(!job.should_run(600));
+        assert!(job.should_run(650));
+    }
+
+    #[test]
+    fn survives_time_wraparound() {
+        let mut job = PeriodicJob::new(100);
+        assert!(job.should_run(u32::MAX - 50));
+        assert!(!job.should_run(u32::MAX));
+        assert!(!job.should_run(48));
+        assert!(job.should_run(49));
+    }
+
+    #[test]
+    fn zero_period_runs_every_millisecond() {
+        let mut job = PeriodicJob::new(0);
+        assert_eq!(job.period_ms(), 1);
+        assert!(job.should_run(5));
+        assert!(!job.should_run(5));
+        assert!(job.should_run(6));
+    }
+}
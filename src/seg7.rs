@@ -0,0 +1,166 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Multiplexed 4-digit 7-segment display.
+//!
+//! Segment patterns use bit 0 for segment `a` up to bit 6 for `g`, and bit 7
+//! for the decimal point:
+//!
+//! ```text
+//!  aaa
+//! f   b
+//!  ggg
+//! e   c
+//!  ddd  dp
+//! ```
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::gpio::{Level, Output};
+
+/// Number of digits of the display.
+pub const DIGITS: usize = 4;
+
+/// Segment patterns for the digits 0 to 9.
+pub const DIGIT_PATTERNS: [u8; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+
+/// All segments off.
+pub const BLANK: u8 = 0x00;
+
+/// Segment `g` only, a minus sign.
+pub const MINUS: u8 = 0x40;
+
+/// Segment patterns showing `n`, most significant digit first.
+///
+/// Leading zeros are blanked. Numbers above 9999 don't fit and are shown as
+/// `----`.
+pub fn encode_number(n: u16) -> [u8; DIGITS] {
+    if n > 9999 {
+        return [MINUS; DIGITS];
+    }
+    let mut patterns = [BLANK; DIGITS];
+    let mut rest = n;
+    for (i, pattern) in patterns.iter_mut().enumerate().rev() {
+        // The last digit is always shown, so 0 reads as 0 and not as blank
+        if rest == 0 && i < DIGITS - 1 {
+            break;
+        }
+        *pattern = DIGIT_PATTERNS[usize::from(rest % 10)];
+        rest /= 10;
+    }
+    patterns
+}
+
+/// Common-cathode 4-digit display with segments and digits on GPIO outputs.
+///
+/// Only one digit is lit at a time. [`refresh`](Self::refresh) moves to the
+/// next one and must be called at a steady rate, fast enough for the eye to
+/// merge the digits into a still image.
+#[cfg(target_arch = "arm")]
+pub struct SevenSegment<'d> {
+    segments: [Output<'d>; 8],
+    digits: [Output<'d>; DIGITS],
+    buffer: [u8; DIGITS],
+    current: usize,
+}
+
+#[cfg(target_arch = "arm")]
+impl<'d> SevenSegment<'d> {
+    /// Create a display from the segment outputs `a` to `g` and `dp`, and the
+    /// digit outputs from left to right.
+    ///
+    /// Segments are on when high; a digit is selected when its output is
+    /// low, sinking the current of its segments.
+    pub fn new(segments: [Output<'d>; 8], digits: [Output<'d>; DIGITS]) -> Self {
+        let mut display = Self {
+            segments,
+            digits,
+            buffer: [BLANK; DIGITS],
+            current: 0,
+        };
+        for digit in display.digits.iter_mut() {
+            digit.set_high();
+        }
+        display
+    }
+
+    /// Show `n` from the next refresh on.
+    pub fn display_number(&mut self, n: u16) {
+        self.buffer = encode_number(n);
+    }
+
+    /// Switch from the digit currently lit to the next one.
+    pub fn refresh(&mut self) {
+        // Turn the old digit off before changing the segments, or its
+        // neighbour's pattern would flash on it briefly (ghosting)
+        self.digits[self.current].set_high();
+        self.current = (self.current + 1) % DIGITS;
+
+        let pattern = self.buffer[self.current];
+        for (bit, segment) in self.segments.iter_mut().enumerate() {
+            segment.set_level(Level::from(pattern & (1 << bit) != 0));
+        }
+        self.digits[self.current].set_low();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_digit_number() {
+        assert_eq!(
+            encode_number(1234),
+            [
+                DIGIT_PATTERNS[1],
+                DIGIT_PATTERNS[2],
+                DIGIT_PATTERNS[3],
+                DIGIT_PATTERNS[4]
+            ]
+        );
+    }
+
+    #[test]
+    fn leading_zeros_are_blank() {
+        assert_eq!(encode_number(42), [BLANK, BLANK, DIGIT_PATTERNS[4], DIGIT_PATTERNS[2]]);
+        assert_eq!(encode_number(0), [BLANK, BLANK, BLANK, DIGIT_PATTERNS[0]]);
+    }
+
+    #[test]
+    fn inner_zeros_are_shown() {
+        assert_eq!(
+            encode_number(1005),
+            [
+                DIGIT_PATTERNS[1],
+                DIGIT_PATTERNS[0],
+                DIGIT_PATTERNS[0],
+                DIGIT_PATTERNS[5]
+            ]
+        );
+    }
+
+    #[test]
+    fn too_large_numbers_show_dashes() {
+        assert_eq!(encode_number(9999)[0], DIGIT_PATTERNS[9]);
+        assert_eq!(encode_number(10000), [MINUS; DIGITS]);
+        assert_eq!(encode_number(u16::MAX), [MINUS; DIGITS]);
+    }
+
+    #[test]
+    fn digit_patterns_light_the_right_segment_count() {
+        let counts = DIGIT_PATTERNS.map(|p| p.count_ones());
+        assert_eq!(counts, [6, 2, 5, 5, 4, 5, 6, 3, 7, 6]);
+    }
+}
@@ -0,0 +1,147 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Serial port helpers.
+
+use defmt::{panic, warn};
+use embassy_stm32::mode::Async;
+use embassy_stm32::usart::{self, Config, DataBits, Parity, StopBits, UartRx};
+use embedded_hal::serial::Read;
+use heapless::String;
+
+/// UART configuration with the given baud rate and 8 data bits, no parity
+/// and 1 stop bit (8N1), the settings most terminals expect.
+///
+/// `Config` can't be built with a struct literal, as the driver may add
+/// fields; start from this and change the fields that differ.
+pub fn uart_config(baud: u32) -> Config {
+    let mut config = Config::default();
+    config.baudrate = baud;
+    config.data_bits = DataBits::DataBits8;
+    config.parity = Parity::ParityNone;
+    config.stop_bits = StopBits::STOP1;
+    config
+}
+
+/// Read one line of text into `line`.
+///
+/// A line ends at `\n` or `\r`; the terminator is not stored. Empty lines are
+/// skipped, so a `\r\n` sequence yields a single line. Non-ASCII bytes are
+/// ignored. If the line does not fit in `line` it is truncated and the rest of
+/// it is discarded up to the terminator.
+///
+/// On a receive error the rest of the broken line is discarded up to its
+/// terminator before the error is returned, so the next call starts on a
+/// fresh line. If the error hit the terminator itself, the following line is
+/// discarded with it.
+pub async fn read_line<const N: usize>(
+    rx: &mut UartRx<'_, Async>,
+    line: &mut String<N>,
+) -> Result<(), usart::Error> {
+    line.clear();
+    let mut truncated = false;
+    let mut byte = [0u8; 1];
+
+    loop {
+        if let Err(e) = rx.read(&mut byte).await {
+            skip_line(rx).await;
+            return Err(e);
+        }
+        match byte[0] {
+            b'\r' | b'\n' => {
+                if truncated {
+                    warn!("Line longer than {} bytes, truncated", N);
+                    return Ok(());
+                }
+                if !line.is_empty() {
+                    return Ok(());
+                }
+            }
+            b if b.is_ascii() => {
+                if line.push(b as char).is_err() {
+                    truncated = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read and discard bytes up to the next line terminator.
+async fn skip_line(rx: &mut UartRx<'_, Async>) {
+    let mut byte = [0u8; 1];
+    loop {
+        // Further errors are part of the same broken line
+        if rx.read(&mut byte).await.is_ok() && matches!(byte[0], b'\r' | b'\n') {
+            return;
+        }
+    }
+}
+
+/// Discard everything the receiver holds after an error, and return the
+/// number of bytes dropped.
+///
+/// A blocking read reports an error before it hands out the byte received
+/// with it, so without a flush the next read returns that corrupted byte.
+/// Each call to `read` clears one error flag or takes one byte, until
+/// nothing is pending.
+pub fn drain_rx<R: Read<u8>>(rx: &mut R) -> usize {
+    let mut dropped = 0;
+    loop {
+        match rx.read() {
+            Ok(_) => dropped += 1,
+            Err(nb::Error::Other(_)) => {}
+            Err(nb::Error::WouldBlock) => return dropped,
+        }
+    }
+}
+
+/// Human-readable description of a UART receive or transmit error.
+pub fn uart_error_message(e: usart::Error) -> &'static str {
+    match e {
+        usart::Error::Framing => "framing error (check baud rate and wiring)",
+        usart::Error::Noise => "noise detected on the line",
+        usart::Error::Overrun => "overrun, a byte arrived before the previous one was read",
+        usart::Error::Parity => "parity error",
+        usart::Error::BufferTooLong => "buffer too long for a single DMA transfer",
+        _ => "unknown error",
+    }
+}
+
+/// Log a UART error with a readable message.
+///
+/// It only logs. After an error from a blocking read, flush the receiver
+/// with [`drain_rx`]; DMA reads and [`read_line`] recover on their own.
+pub fn log_uart_error(e: usart::Error) {
+    warn!("UART {}", uart_error_message(e));
+}
+
+/// Human-readable description of a UART configuration error.
+pub fn config_error_message(e: usart::ConfigError) -> &'static str {
+    match e {
+        usart::ConfigError::BaudrateTooLow => "baud rate too low",
+        usart::ConfigError::BaudrateTooHigh => "baud rate too high",
+        usart::ConfigError::RxOrTxNotEnabled => "RX or TX not enabled",
+    }
+}
+
+/// Unwrap the result of a UART constructor, halting with a readable message
+/// if the configuration was rejected.
+pub fn uart_or_halt<T>(result: Result<T, usart::ConfigError>) -> T {
+    match result {
+        Ok(uart) => uart,
+        Err(e) => panic!("UART configuration failed: {}", config_error_message(e)),
+    }
+}
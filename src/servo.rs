@@ -0,0 +1,163 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Hobby servo driven by a PWM channel.
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::timer::simple_pwm::SimplePwmChannel;
+#[cfg(target_arch = "arm")]
+use embassy_stm32::timer::GeneralInstance4Channel;
+
+/// PWM period expected by hobby servos (50 Hz), in microseconds.
+pub const PERIOD_US: u32 = 20_000;
+
+/// Pulse width at 0°, in microseconds, for a standard servo.
+pub const DEFAULT_MIN_US: u32 = 1_000;
+
+/// Pulse width at 180°, in microseconds, for a standard servo.
+pub const DEFAULT_MAX_US: u32 = 2_000;
+
+/// Compute the duty cycle giving a pulse for `degrees`.
+///
+/// The angle is clamped to 0–180° and mapped linearly onto `min_us..=max_us`
+/// within a [`PERIOD_US`] period; `max_duty` is the duty value for 100%.
+/// `min_us` may be greater than `max_us`, which reverses the direction.
+pub fn angle_to_duty(degrees: f32, min_us: u32, max_us: u32, max_duty: u16) -> u16 {
+    let degrees = degrees.clamp(0.0, 180.0);
+    let pulse_us = min_us as f32 + (max_us as f32 - min_us as f32) * degrees / 180.0;
+    (pulse_us * max_duty as f32 / PERIOD_US as f32) as u16
+}
+
+/// Compute the duty cycle giving a pulse of `pulse_us` microseconds.
+///
+/// The pulse is clamped to one [`PERIOD_US`] period; `max_duty` is the duty
+/// value for 100%.
+pub fn pulse_to_duty(pulse_us: u32, max_duty: u16) -> u16 {
+    (u64::from(pulse_us.min(PERIOD_US)) * u64::from(max_duty) / u64::from(PERIOD_US)) as u16
+}
+
+/// Limit `pulse_us` to the range between `min_us` and `max_us`, in either order.
+pub fn clamp_pulse(pulse_us: u32, min_us: u32, max_us: u32) -> u32 {
+    pulse_us.max(min_us.min(max_us)).min(min_us.max(max_us))
+}
+
+/// Hobby servo on a PWM channel running at 50 Hz.
+#[cfg(target_arch = "arm")]
+pub struct Servo<'d, T: GeneralInstance4Channel> {
+    channel: SimplePwmChannel<'d, T>,
+    min_us: u32,
+    max_us: u32,
+}
+
+#[cfg(target_arch = "arm")]
+impl<'d, T: GeneralInstance4Channel> Servo<'d, T> {
+    /// Create a servo on `channel` and enable its output.
+    ///
+    /// `min_us` and `max_us` are the pulse widths at 0° and 180°. They differ
+    /// slightly from one servo to another and can be tuned to reach the full
+    /// range of motion ([`DEFAULT_MIN_US`] and [`DEFAULT_MAX_US`] are a safe start).
+    pub fn new(mut channel: SimplePwmChannel<'d, T>, min_us: u32, max_us: u32) -> Self {
+        channel.enable();
+        Self {
+            channel,
+            min_us,
+            max_us,
+        }
+    }
+
+    /// Move the servo to `degrees`, clamped to 0–180°.
+    pub fn set_angle(&mut self, degrees: f32) {
+        let duty = angle_to_duty(degrees, self.min_us, self.max_us, self.channel.max_duty_cycle());
+        self.channel.set_duty_cycle(duty);
+    }
+
+    /// Output a pulse of `pulse_us` microseconds, clamped between `min_us`
+    /// and `max_us`.
+    pub fn set_pulse_us(&mut self, pulse_us: u32) {
+        let pulse_us = clamp_pulse(pulse_us, self.min_us, self.max_us);
+        let duty = pulse_to_duty(pulse_us, self.channel.max_duty_cycle());
+        self.channel.set_duty_cycle(duty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_DUTY: u16 = 20_000;
+
+    #[test]
+    fn end_points_match_pulse_widths() {
+        // With max_duty equal to PERIOD_US, one duty count is one microsecond
+        assert_eq!(angle_to_duty(0.0, DEFAULT_MIN_US, DEFAULT_MAX_US, MAX_DUTY), 1_000);
+        assert_eq!(angle_to_duty(180.0, DEFAULT_MIN_US, DEFAULT_MAX_US, MAX_DUTY), 2_000);
+    }
+
+    #[test]
+    fn middle_is_halfway() {
+        assert_eq!(angle_to_duty(90.0, DEFAULT_MIN_US, DEFAULT_MAX_US, MAX_DUTY), 1_500);
+        assert_eq!(angle_to_duty(90.0, 500, 2_500, MAX_DUTY), 1_500);
+    }
+
+    #[test]
+    fn angle_is_clamped() {
+        assert_eq!(angle_to_duty(-45.0, DEFAULT_MIN_US, DEFAULT_MAX_US, MAX_DUTY), 1_000);
+        assert_eq!(angle_to_duty(270.0, DEFAULT_MIN_US, DEFAULT_MAX_US, MAX_DUTY), 2_000);
+    }
+
+    #[test]
+    fn reversed_range_runs_backwards() {
+        assert_eq!(angle_to_duty(0.0, DEFAULT_MAX_US, DEFAULT_MIN_US, MAX_DUTY), 2_000);
+        assert_eq!(angle_to_duty(45.0, DEFAULT_MAX_US, DEFAULT_MIN_US, MAX_DUTY), 1_750);
+        assert_eq!(angle_to_duty(180.0, DEFAULT_MAX_US, DEFAULT_MIN_US, MAX_DUTY), 1_000);
+    }
+
+    #[test]
+    fn scales_with_max_duty() {
+        // 1.5 ms out of 20 ms is 7.5% of the duty range
+        assert_eq!(angle_to_duty(90.0, DEFAULT_MIN_US, DEFAULT_MAX_US, 4_000), 300);
+    }
+
+    #[test]
+    fn pulse_matches_angle() {
+        assert_eq!(pulse_to_duty(1_000, MAX_DUTY), 1_000);
+        assert_eq!(pulse_to_duty(1_500, 4_000), 300);
+        assert_eq!(
+            pulse_to_duty(1_750, 4_000),
+            angle_to_duty(135.0, DEFAULT_MIN_US, DEFAULT_MAX_US, 4_000)
+        );
+    }
+
+    #[test]
+    fn pulse_is_clamped_to_the_range() {
+        assert_eq!(clamp_pulse(500, DEFAULT_MIN_US, DEFAULT_MAX_US), 1_000);
+        assert_eq!(clamp_pulse(1_500, DEFAULT_MIN_US, DEFAULT_MAX_US), 1_500);
+        assert_eq!(clamp_pulse(2_500, DEFAULT_MIN_US, DEFAULT_MAX_US), 2_000);
+    }
+
+    #[test]
+    fn reversed_servo_clamps_without_panicking() {
+        // Servo::new(channel, 2000, 1000) is a valid calibration
+        assert_eq!(clamp_pulse(500, DEFAULT_MAX_US, DEFAULT_MIN_US), 1_000);
+        assert_eq!(clamp_pulse(1_500, DEFAULT_MAX_US, DEFAULT_MIN_US), 1_500);
+        assert_eq!(clamp_pulse(2_500, DEFAULT_MAX_US, DEFAULT_MIN_US), 2_000);
+    }
+
+    #[test]
+    fn pulse_is_clamped_to_the_period() {
+        assert_eq!(pulse_to_duty(0, MAX_DUTY), 0);
+        assert_eq!(pulse_to_duty(30_000, MAX_DUTY), MAX_DUTY);
+    }
+}
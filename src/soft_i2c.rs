@@ -0,0 +1,225 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bit-banged I2C master on two GPIOs.
+//!
+//! For sensors wired to pins that no I2C peripheral can use. It implements
+//! the `embedded-hal-async` [`I2c`] trait, so drivers such as the `hts221`
+//! and `lsm6dsl` modules work with it unchanged.
+//!
+//! The transfer itself busy-waits: the `async` methods return only once the
+//! whole transaction is done, blocking the executor meanwhile (about 0.1 ms
+//! per byte at 100 kHz).
+
+use cortex_m::asm;
+use embassy_stm32::gpio::OutputOpenDrain;
+use embassy_stm32::time::Hertz;
+use embassy_time::{Duration, Instant};
+use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+/// Longest a device may hold SCL low (clock stretching) before giving up.
+pub const STRETCH_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Bit-banged I2C error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// No device acknowledged the address.
+    AddressNack,
+    /// The device refused a data byte.
+    DataNack,
+    /// A device held SCL low for longer than [`STRETCH_TIMEOUT`].
+    ClockStretchTimeout,
+    /// SDA or SCL was low before the start condition: another master is
+    /// active or a device is stuck.
+    BusBusy,
+}
+
+impl embedded_hal_async::i2c::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::AddressNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Error::DataNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            Error::ClockStretchTimeout | Error::BusBusy => ErrorKind::Bus,
+        }
+    }
+}
+
+/// I2C master on two open-drain pins.
+///
+/// Both lines need pull-ups, 4.7 kΩ to 3V3 for 100 kHz. The internal pull-ups
+/// (about 40 kΩ) only work for short wires at low speed.
+pub struct SoftI2c<'d> {
+    scl: OutputOpenDrain<'d>,
+    sda: OutputOpenDrain<'d>,
+    half_period_cycles: u32,
+}
+
+impl<'d> SoftI2c<'d> {
+    /// Create a bus clocked at about `frequency`; both pins should start high.
+    ///
+    /// `core_clock` is the CPU clock, 16 MHz after `embassy_stm32::init` with
+    /// the default config: the bit timing is derived from it. The time spent
+    /// toggling pins comes on top, so the real clock is somewhat slower.
+    pub fn new(scl: OutputOpenDrain<'d>, sda: OutputOpenDrain<'d>, core_clock: Hertz, frequency: Hertz) -> Self {
+        let half_period_cycles = core_clock.0 / frequency.0.max(1) / 2;
+        Self {
+            scl,
+            sda,
+            half_period_cycles,
+        }
+    }
+
+    fn delay(&self) {
+        asm::delay(self.half_period_cycles);
+    }
+
+    /// Release SCL and wait until it is really high.
+    ///
+    /// A slave that needs more time holds SCL low after the master releases
+    /// it (clock stretching); the bit only continues once it lets go.
+    fn release_scl(&mut self) -> Result<(), Error> {
+        self.scl.set_high();
+        let start = Instant::now();
+        while self.scl.is_low() {
+            if start.elapsed() > STRETCH_TIMEOUT {
+                return Err(Error::ClockStretchTimeout);
+            }
+        }
+        Ok(())
+    }
+
+    /// START (or repeated START): SDA falls while SCL is high.
+    fn start(&mut self) -> Result<(), Error> {
+        self.sda.set_high();
+        self.delay();
+        self.release_scl()?;
+        if self.sda.is_low() {
+            return Err(Error::BusBusy);
+        }
+        self.delay();
+        self.sda.set_low();
+        self.delay();
+        self.scl.set_low();
+        Ok(())
+    }
+
+    /// STOP: SDA rises while SCL is high, leaving the bus idle.
+    fn stop(&mut self) -> Result<(), Error> {
+        self.sda.set_low();
+        self.delay();
+        self.release_scl()?;
+        self.delay();
+        self.sda.set_high();
+        self.delay();
+        Ok(())
+    }
+
+    /// SDA may only change while SCL is low; the slave samples it while SCL is high.
+    fn write_bit(&mut self, bit: bool) -> Result<(), Error> {
+        self.sda.set_level(bit.into());
+        self.delay();
+        self.release_scl()?;
+        self.delay();
+        self.scl.set_low();
+        Ok(())
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Error> {
+        // Released, so the slave can pull SDA low
+        self.sda.set_high();
+        self.delay();
+        self.release_scl()?;
+        self.delay();
+        let bit = self.sda.is_high();
+        self.scl.set_low();
+        Ok(bit)
+    }
+
+    /// Send a byte, MSB first; returns whether the slave acknowledged it.
+    fn write_byte(&mut self, byte: u8) -> Result<bool, Error> {
+        for i in (0..8).rev() {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+        // ACK is the slave pulling SDA low during the ninth clock
+        Ok(!self.read_bit()?)
+    }
+
+    /// Receive a byte, MSB first, then ACK it to ask for more or NACK it to end the read.
+    fn read_byte(&mut self, ack: bool) -> Result<u8, Error> {
+        let mut byte = 0;
+        for _ in 0..8 {
+            byte = (byte << 1) | u8::from(self.read_bit()?);
+        }
+        self.write_bit(!ack)?;
+        Ok(byte)
+    }
+
+    fn run(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        let mut previous_read = None;
+        for i in 0..operations.len() {
+            let read = matches!(operations[i], Operation::Read(_));
+            let next_read = matches!(operations.get(i + 1), Some(Operation::Read(_)));
+
+            // Consecutive operations of the same kind continue the same
+            // transfer; a change of direction needs a repeated START
+            if previous_read != Some(read) {
+                self.start()?;
+                if !self.write_byte((address << 1) | u8::from(read))? {
+                    return Err(Error::AddressNack);
+                }
+            }
+
+            match &mut operations[i] {
+                Operation::Write(bytes) => {
+                    for &byte in bytes.iter() {
+                        if !self.write_byte(byte)? {
+                            return Err(Error::DataNack);
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    // The last byte of a read is NACKed, telling the slave to release SDA
+                    let count = buffer.len();
+                    for (j, byte) in buffer.iter_mut().enumerate() {
+                        let last = j + 1 == count && !next_read;
+                        *byte = self.read_byte(!last)?;
+                    }
+                }
+            }
+            previous_read = Some(read);
+        }
+        Ok(())
+    }
+}
+
+impl ErrorType for SoftI2c<'_> {
+    type Error = Error;
+}
+
+impl I2c for SoftI2c<'_> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let result = self.run(address, operations);
+        // Always end with STOP, even after an error, so the bus is idle again.
+        // A busy bus was never started, so there is nothing to stop.
+        if result == Err(Error::BusBusy) {
+            return result;
+        }
+        let stopped = self.stop();
+        result.and(stopped)
+    }
+}
@@ -0,0 +1,62 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Software PWM on any output pin.
+//!
+//! The pin is toggled by a task using `embassy_time` timers, so no timer
+//! channel is needed. Timings are rounded to the embassy-time tick (about
+//! 30 us at 32.768 kHz) and delayed by whatever else the executor is
+//! running: keep the frequency at a few hundred Hz at most. At 100 Hz a
+//! period spans about 330 ticks, enough for a smooth LED fade.
+
+use embassy_stm32::gpio::Output;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Timer};
+
+/// Duty cycle requested for the running soft PWM, from 0.0 to 1.0.
+pub static DUTY: Signal<CriticalSectionRawMutex, f32> = Signal::new();
+
+/// Change the duty cycle of the running soft PWM, clamped to 0.0..=1.0.
+///
+/// The new value applies from the next PWM period.
+pub fn set_duty(duty: f32) {
+    DUTY.signal(duty);
+}
+
+/// Drive `pin` with a PWM signal of `frequency_hz`, initially off.
+#[embassy_executor::task]
+pub async fn soft_pwm_task(mut pin: Output<'static>, frequency_hz: u32) {
+    let period = Duration::from_hz(frequency_hz as u64);
+    let mut duty = 0.0;
+
+    loop {
+        if let Some(new_duty) = DUTY.try_take() {
+            duty = new_duty.clamp(0.0, 1.0);
+        }
+
+        let high = Duration::from_ticks((period.as_ticks() as f32 * duty) as u64);
+        let low = period - high;
+
+        if high.as_ticks() > 0 {
+            pin.set_high();
+            Timer::after(high).await;
+        }
+        if low.as_ticks() > 0 {
+            pin.set_low();
+            Timer::after(low).await;
+        }
+    }
+}
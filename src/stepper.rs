@@ -0,0 +1,175 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Unipolar stepper motor, such as the 28BYJ-48, through a ULN2003 driver.
+
+#[cfg(target_arch = "arm")]
+use embassy_stm32::gpio::Output;
+#[cfg(target_arch = "arm")]
+use embassy_time::{Duration, Timer};
+
+/// Coil pattern for each half step, as `[IN1, IN2, IN3, IN4]`.
+///
+/// Alternating one and two energized coils gives twice the resolution of
+/// full stepping and smoother motion.
+pub const HALF_STEP_SEQUENCE: [[bool; 4]; 8] = [
+    [true, false, false, false],
+    [true, true, false, false],
+    [false, true, false, false],
+    [false, true, true, false],
+    [false, false, true, false],
+    [false, false, true, true],
+    [false, false, false, true],
+    [true, false, false, true],
+];
+
+/// Half steps per revolution of the 28BYJ-48 output shaft: 64 per motor
+/// revolution, times its nominal 1:64 gearbox.
+pub const STEPS_PER_REVOLUTION: i32 = 4096;
+
+/// Position in the half-step sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepSequence {
+    index: usize,
+}
+
+impl StepSequence {
+    /// Start at the first pattern of the sequence.
+    pub const fn new() -> Self {
+        Self { index: 0 }
+    }
+
+    /// Coil pattern at the current position.
+    pub fn pattern(&self) -> [bool; 4] {
+        HALF_STEP_SEQUENCE[self.index]
+    }
+
+    /// Move one half step, forward or backward, and return the new pattern.
+    ///
+    /// The sequence is circular: after the last pattern comes the first one
+    /// again, and before the first one the last.
+    pub fn advance(&mut self, forward: bool) -> [bool; 4] {
+        let len = HALF_STEP_SEQUENCE.len();
+        self.index = if forward {
+            (self.index + 1) % len
+        } else {
+            (self.index + len - 1) % len
+        };
+        self.pattern()
+    }
+}
+
+/// Stepper motor driven by four GPIO outputs.
+#[cfg(target_arch = "arm")]
+pub struct Stepper<'d> {
+    coils: [Output<'d>; 4],
+    sequence: StepSequence,
+    position: i32,
+}
+
+#[cfg(target_arch = "arm")]
+impl<'d> Stepper<'d> {
+    /// Create a stepper on the outputs wired to IN1 to IN4 of the driver.
+    ///
+    /// The coils stay off until the first step.
+    pub fn new(coils: [Output<'d>; 4]) -> Self {
+        let mut stepper = Self {
+            coils,
+            sequence: StepSequence::new(),
+            position: 0,
+        };
+        stepper.release();
+        stepper
+    }
+
+    /// Move `steps` half steps, forward for positive values and backward for
+    /// negative ones, waiting `delay` after each.
+    ///
+    /// The coils stay energized afterwards, which holds the shaft in place.
+    pub async fn step(&mut self, steps: i32, delay: Duration) {
+        let forward = steps >= 0;
+        for _ in 0..steps.unsigned_abs() {
+            let pattern = self.sequence.advance(forward);
+            self.energize(pattern);
+            self.position += if forward { 1 } else { -1 };
+            Timer::after(delay).await;
+        }
+    }
+
+    /// Switch all coils off: the motor stops drawing current and can be
+    /// turned by hand.
+    pub fn release(&mut self) {
+        self.energize([false; 4]);
+    }
+
+    /// Half steps moved since creation, positive forward.
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    fn energize(&mut self, pattern: [bool; 4]) {
+        for (coil, on) in self.coils.iter_mut().zip(pattern) {
+            coil.set_level(on.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_step_changes_one_coil() {
+        for (i, pattern) in HALF_STEP_SEQUENCE.iter().enumerate() {
+            let next = HALF_STEP_SEQUENCE[(i + 1) % HALF_STEP_SEQUENCE.len()];
+            let changed = pattern.iter().zip(next).filter(|&(&a, b)| a != b).count();
+            assert_eq!(changed, 1);
+        }
+    }
+
+    #[test]
+    fn forward_wraps_to_the_start() {
+        let mut sequence = StepSequence::new();
+        for _ in 0..HALF_STEP_SEQUENCE.len() - 1 {
+            sequence.advance(true);
+        }
+        assert_eq!(sequence.pattern(), HALF_STEP_SEQUENCE[7]);
+        assert_eq!(sequence.advance(true), HALF_STEP_SEQUENCE[0]);
+    }
+
+    #[test]
+    fn backward_wraps_to_the_end() {
+        let mut sequence = StepSequence::new();
+        assert_eq!(sequence.advance(false), HALF_STEP_SEQUENCE[7]);
+        assert_eq!(sequence.advance(false), HALF_STEP_SEQUENCE[6]);
+    }
+
+    #[test]
+    fn back_and_forth_returns_to_the_same_pattern() {
+        let mut sequence = StepSequence::new();
+        for _ in 0..13 {
+            sequence.advance(true);
+        }
+        let pattern = sequence.pattern();
+        for _ in 0..13 {
+            sequence.advance(false);
+        }
+        for _ in 0..13 {
+            sequence.advance(true);
+        }
+        assert_eq!(sequence.pattern(), pattern);
+        assert_eq!(sequence.advance(false), HALF_STEP_SEQUENCE[4]);
+    }
+}
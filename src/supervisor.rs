@@ -0,0 +1,121 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Task liveness tracking for a watchdog supervisor.
+//!
+//! Each supervised task checks in regularly. A single supervisor task pets
+//! the hardware watchdog only while every task has checked in recently, so
+//! one stalled task is enough to reset the board, even if the others, and
+//! the supervisor itself, keep running.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Last check-in time of `N` tasks, in milliseconds.
+///
+/// Times are `u32` milliseconds compared with wrapping arithmetic, which
+/// stays correct across the wraparound after 49 days. All tasks start as
+/// if they had checked in at time 0, so the first window after boot also
+/// covers the time tasks need to start. Get the current time with
+/// `timing::now_ms`.
+pub struct Supervisor<const N: usize> {
+    last_check_in: [AtomicU32; N],
+}
+
+impl<const N: usize> Supervisor<N> {
+    /// Create a supervisor for `N` tasks, identified as `0..N`.
+    pub const fn new() -> Self {
+        Self {
+            last_check_in: [const { AtomicU32::new(0) }; N],
+        }
+    }
+
+    /// Record that `task` is alive at `now_ms`.
+    ///
+    /// Unknown task numbers are ignored.
+    pub fn check_in(&self, task: usize, now_ms: u32) {
+        if let Some(last) = self.last_check_in.get(task) {
+            last.store(now_ms, Ordering::Relaxed);
+        }
+    }
+
+    /// The first task that has not checked in for more than `window_ms`,
+    /// or `None` if all are alive.
+    pub fn stalled(&self, now_ms: u32, window_ms: u32) -> Option<usize> {
+        self.last_check_in
+            .iter()
+            .position(|last| now_ms.wrapping_sub(last.load(Ordering::Relaxed)) > window_ms)
+    }
+}
+
+impl<const N: usize> Default for Supervisor<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_alive_at_start() {
+        let supervisor = Supervisor::<3>::new();
+        assert_eq!(supervisor.stalled(500, 1000), None);
+    }
+
+    #[test]
+    fn task_that_never_checks_in_stalls() {
+        let supervisor = Supervisor::<2>::new();
+        supervisor.check_in(0, 900);
+        assert_eq!(supervisor.stalled(1000, 1000), None);
+        assert_eq!(supervisor.stalled(1001, 1000), Some(1));
+    }
+
+    #[test]
+    fn check_ins_keep_tasks_alive() {
+        let supervisor = Supervisor::<2>::new();
+        for now in (0..10_000).step_by(400) {
+            supervisor.check_in(0, now);
+            supervisor.check_in(1, now);
+            assert_eq!(supervisor.stalled(now + 300, 1000), None);
+        }
+    }
+
+    #[test]
+    fn reports_the_stalled_task() {
+        let supervisor = Supervisor::<3>::new();
+        supervisor.check_in(0, 5000);
+        supervisor.check_in(1, 3000);
+        supervisor.check_in(2, 5000);
+        assert_eq!(supervisor.stalled(5500, 1000), Some(1));
+        supervisor.check_in(1, 5500);
+        assert_eq!(supervisor.stalled(5500, 1000), None);
+    }
+
+    #[test]
+    fn survives_the_millisecond_wraparound() {
+        let supervisor = Supervisor::<1>::new();
+        supervisor.check_in(0, u32::MAX - 100);
+        assert_eq!(supervisor.stalled(200, 1000), None);
+        assert_eq!(supervisor.stalled(1000, 1000), Some(0));
+    }
+
+    #[test]
+    fn unknown_task_is_ignored() {
+        let supervisor = Supervisor::<1>::new();
+        supervisor.check_in(5, 100);
+        assert_eq!(supervisor.stalled(100, 1000), None);
+    }
+}
@@ -0,0 +1,117 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Binary telemetry frames for a host to decode.
+//!
+//! A [`Telemetry`] record is serialized with `postcard`, a compact binary
+//! format for `serde`, and framed with COBS (Consistent Overhead Byte
+//! Stuffing). COBS removes every zero byte from the data and ends the frame
+//! with a single zero, so a receiver that starts listening halfway through
+//! a stream finds the next frame boundary at the next zero.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest encoded frame, in bytes.
+///
+/// Postcard stores integers as varints, at most 5 bytes for a `u32` and 3
+/// for a `u16`, plus 4 bytes for the `f32` and 1 for the `bool`. COBS adds
+/// one byte in front and the zero terminator at the end.
+pub const MAX_FRAME_SIZE: usize = 5 + 3 + 4 + 1 + 2;
+
+/// One telemetry sample.
+///
+/// The field order is part of the format: postcard writes the fields one
+/// after the other without names, so the host must decode them in the same
+/// order.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, defmt::Format)]
+pub struct Telemetry {
+    /// Time since boot, in milliseconds.
+    pub timestamp_ms: u32,
+    /// Voltage on the analog input, in millivolts.
+    pub adc_mv: u16,
+    /// MCU temperature, in °C.
+    pub temperature_c: f32,
+    /// Whether the user button is held down.
+    pub button_pressed: bool,
+}
+
+impl Telemetry {
+    /// Serialize into `buf` as a COBS frame, including the zero terminator.
+    ///
+    /// Returns the used part of `buf`; a buffer of [`MAX_FRAME_SIZE`] bytes
+    /// always fits.
+    pub fn encode<'a>(&self, buf: &'a mut [u8]) -> postcard::Result<&'a mut [u8]> {
+        postcard::to_slice_cobs(self, buf)
+    }
+
+    /// Decode a COBS frame, with or without its zero terminator.
+    ///
+    /// The frame is decoded in place, so its contents are overwritten.
+    pub fn decode(frame: &mut [u8]) -> postcard::Result<Self> {
+        postcard::from_bytes_cobs(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: Telemetry = Telemetry {
+        timestamp_ms: 123_456,
+        adc_mv: 1_650,
+        temperature_c: 27.5,
+        button_pressed: true,
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut buf = [0; MAX_FRAME_SIZE];
+        let frame = SAMPLE.encode(&mut buf).unwrap();
+        assert_eq!(Telemetry::decode(frame).unwrap(), SAMPLE);
+    }
+
+    #[test]
+    fn frame_ends_at_the_only_zero() {
+        let zeros = Telemetry {
+            timestamp_ms: 0,
+            adc_mv: 0,
+            temperature_c: 0.0,
+            button_pressed: false,
+        };
+        let mut buf = [0xFF; MAX_FRAME_SIZE];
+        let frame = zeros.encode(&mut buf).unwrap();
+        let (last, data) = frame.split_last().unwrap();
+        assert_eq!(*last, 0);
+        assert!(data.iter().all(|&b| b != 0));
+    }
+
+    #[test]
+    fn largest_values_fit() {
+        let largest = Telemetry {
+            timestamp_ms: u32::MAX,
+            adc_mv: u16::MAX,
+            temperature_c: f32::MAX,
+            button_pressed: true,
+        };
+        let mut buf = [0; MAX_FRAME_SIZE];
+        assert_eq!(largest.encode(&mut buf).unwrap().len(), MAX_FRAME_SIZE);
+    }
+
+    #[test]
+    fn small_buffer_is_an_error() {
+        let mut buf = [0; 4];
+        assert!(SAMPLE.encode(&mut buf).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Delays and sleeps with one set of names.
+//!
+//! | Helper | Waits by | Use it for |
+//! |---|---|---|
+//! | [`sleep_ms`], [`sleep_us`] | yielding to the executor | almost everything in async code |
+//! | [`delay_ms`], [`delay_us`] | spinning the CPU | short waits before the executor matters, or outside async code |
+//!
+//! A sleep lets other tasks run and lets the executor put the core to sleep
+//! until the timer fires. A delay keeps the CPU busy and blocks every task
+//! on the same executor, so it is only right for short waits, such as the
+//! few microseconds a sensor needs after being enabled, or for code that
+//! can't `.await` (interrupt handlers, blocking drivers, start-up before
+//! any task runs).
+//!
+//! Both kinds are driven by the embassy time driver, which ticks at
+//! 32.768 kHz, about every 30.5 µs. A wait that starts between two ticks
+//! can end up to one tick early, which only matters for microsecond waits,
+//! so the `_us` helpers add one tick: they never end early, but may take up
+//! to about 60 µs longer than requested.
+//!
+//! Drivers that take a delay as a parameter expect an
+//! `embedded_hal_async::delay::DelayNs` (async) or `embedded_hal::delay::DelayNs`
+//! (blocking) implementation; `embassy_time::Delay` provides both. It waits
+//! for the bare duration without the extra tick, so its microsecond waits can
+//! end up to one tick early.
+//!
+//! None of these work before `embassy_stm32::init` starts the time driver;
+//! `_97_delay_vs_sleep.rs` waits there with a `cortex_m` delay instead.
+
+use embassy_time::{block_for, Duration, Instant, Timer};
+
+/// `us` microseconds plus one tick, so a wait started between two ticks
+/// does not end early.
+fn at_least_us(us: u32) -> Duration {
+    Duration::from_micros(us.into()) + Duration::from_ticks(1)
+}
+
+/// Sleep for `ms` milliseconds, letting other tasks run.
+pub async fn sleep_ms(ms: u32) {
+    Timer::after(Duration::from_millis(ms.into())).await
+}
+
+/// Sleep for `us` microseconds, letting other tasks run.
+pub async fn sleep_us(us: u32) {
+    Timer::after(at_least_us(us)).await
+}
+
+/// Busy-wait for `ms` milliseconds, blocking the executor.
+pub fn delay_ms(ms: u32) {
+    block_for(Duration::from_millis(ms.into()))
+}
+
+/// Busy-wait for `us` microseconds, blocking the executor.
+pub fn delay_us(us: u32) {
+    block_for(at_least_us(us))
+}
+
+/// Milliseconds since boot as a `u32`, wrapping after about 49 days.
+///
+/// This is the time base of `supervisor::Supervisor` and
+/// `scheduler::PeriodicJob`, which compare times with wrapping arithmetic.
+pub fn now_ms() -> u32 {
+    // Truncating keeps the low bits, which is what wrapping comparisons need
+    Instant::now().as_millis() as u32
+}
@@ -0,0 +1,103 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 96-bit unique device ID.
+//!
+//! Every STM32F401 leaves the factory with a 96-bit identifier in system
+//! memory, unique among all STM32 devices. It can't be changed, which makes
+//! it a good source for serial numbers, per-device provisioning or licensing.
+
+use core::fmt::Write;
+
+use heapless::String;
+
+/// Address of the unique device ID (RM0368, "Device electronic signature").
+pub const UID_BASE: usize = 0x1FFF_7A10;
+
+/// Read the unique device ID as three words, bits 31:0 first.
+#[cfg(target_arch = "arm")]
+pub fn read() -> [u32; 3] {
+    let base = UID_BASE as *const u32;
+    // SAFETY: the ID is three readable, aligned words of read-only system memory
+    core::array::from_fn(|i| unsafe { core::ptr::read_volatile(base.add(i)) })
+}
+
+/// Format the ID as 24 hex digits, most significant word first.
+pub fn to_hex(uid: [u32; 3]) -> String<24> {
+    let mut hex = String::new();
+    for word in uid.iter().rev() {
+        // 3 words of 8 digits fill the string exactly
+        let _ = write!(hex, "{:08X}", word);
+    }
+    hex
+}
+
+/// Fold the ID into a 32-bit serial number.
+///
+/// Uses the FNV-1a hash over the 12 bytes. Different devices can in theory
+/// get the same short serial, but with a few boards on a desk it is very
+/// unlikely; use the full ID where uniqueness matters.
+pub fn short_serial(uid: [u32; 3]) -> u32 {
+    let mut hash: u32 = 0x811C_9DC5;
+    for byte in uid.iter().flat_map(|word| word.to_le_bytes()) {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+/// Short human-readable device name, such as `NUCLEO-1A2B3C4D`.
+pub fn device_name(uid: [u32; 3]) -> String<15> {
+    let mut name = String::new();
+    let _ = write!(name, "NUCLEO-{:08X}", short_serial(uid));
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UID: [u32; 3] = [0x0033_0041, 0x3436_5114, 0x3032_3935];
+
+    #[test]
+    fn hex_is_most_significant_word_first() {
+        assert_eq!(to_hex(UID).as_str(), "303239353436511400330041");
+        assert_eq!(to_hex([0; 3]).as_str(), "000000000000000000000000");
+    }
+
+    #[test]
+    fn short_serial_depends_on_every_word() {
+        let serial = short_serial(UID);
+        for i in 0..3 {
+            let mut other = UID;
+            other[i] ^= 1;
+            assert_ne!(short_serial(other), serial);
+        }
+    }
+
+    #[test]
+    fn short_serial_of_zero_id() {
+        // FNV-1a of twelve zero bytes
+        assert_eq!(short_serial([0; 3]), 0xE23C_62B5);
+    }
+
+    #[test]
+    fn device_name_uses_short_serial() {
+        let name = device_name(UID);
+        assert!(name.starts_with("NUCLEO-"));
+        assert_eq!(name.len(), 15);
+        assert_eq!(u32::from_str_radix(&name[7..], 16), Ok(short_serial(UID)));
+    }
+}
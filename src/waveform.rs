@@ -0,0 +1,211 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Lookup-table waveform synthesis and brightness curves.
+
+use core::f32::consts::PI;
+
+use micromath::F32Ext;
+
+/// One period of a sine wave, scaled to `0..=max`.
+///
+/// The first sample is the midpoint, so playback starts without a jump.
+pub fn sine_table<const N: usize>(max: u16) -> [u16; N] {
+    let mut table = [0; N];
+    for (i, sample) in table.iter_mut().enumerate() {
+        let angle = 2.0 * PI * i as f32 / N as f32;
+        *sample = ((0.5 + 0.5 * angle.sin()) * max as f32 + 0.5) as u16;
+    }
+    table
+}
+
+/// Steps through a waveform table at a fixed sample rate.
+///
+/// A 32-bit phase accumulator advances by a fixed increment per sample, so
+/// the output frequency does not have to divide the sample rate evenly.
+pub struct TableStepper {
+    phase: u32,
+    increment: u32,
+}
+
+impl TableStepper {
+    /// Play the table `frequency_hz` times per second when [`next`](Self::next)
+    /// is called `sample_rate_hz` times per second.
+    pub fn new(frequency_hz: f32, sample_rate_hz: f32) -> Self {
+        let increment = (frequency_hz / sample_rate_hz * 4_294_967_296.0) as u32;
+        Self { phase: 0, increment }
+    }
+
+    /// Return the current sample of `table` and advance to the next one.
+    pub fn next<T: Copy>(&mut self, table: &[T]) -> T {
+        let index = ((self.phase as u64 * table.len() as u64) >> 32) as usize;
+        self.phase = self.phase.wrapping_add(self.increment);
+        table[index]
+    }
+}
+
+/// Brightness curve for an LED: `N` duty values rising from 0 to `max`.
+///
+/// The eye responds to light roughly logarithmically, so a linear duty ramp
+/// seems to jump at the dark end and stall at the bright end. Raising the
+/// position in the ramp to the power `gamma` (typically 2.2 to 2.8) makes
+/// equal steps in the table look like equal steps in brightness.
+pub fn gamma_table<const N: usize>(max: u16, gamma: f32) -> [u16; N] {
+    let mut table = [0; N];
+    let last = N.saturating_sub(1).max(1) as f32;
+    for (i, value) in table.iter_mut().enumerate() {
+        let level = i as f32 / last;
+        *value = (level.powf(gamma) * max as f32 + 0.5) as u16;
+    }
+    table
+}
+
+/// Gamma-corrected duty cycles for the 256 perceptual brightness levels.
+///
+/// Entry `level` is `(level / 255)^2.5` scaled to `0..=u16::MAX`. The table
+/// is computed at compile time with integer math, so it costs no start-up
+/// time and no floating point. Use [`gamma`] to scale it to a timer.
+pub const GAMMA_LEVELS: [u16; 256] = gamma_levels();
+
+const fn gamma_levels() -> [u16; 256] {
+    let mut table = [0; 256];
+    let mut level = 0;
+    while level < 256 {
+        // (level / 255)^2.5 = sqrt(level^5 / 255^5), kept in integers
+        let level5 = (level as u128).pow(5);
+        let full = u16::MAX as u128;
+        table[level] = isqrt(level5 * full * full / 255u128.pow(5)) as u16;
+        level += 1;
+    }
+    table
+}
+
+/// Integer square root, rounded down.
+const fn isqrt(value: u128) -> u128 {
+    // Bisection: `low` always satisfies low² <= value
+    let (mut low, mut high) = (0, 1 << 64);
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        if mid * mid <= value {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Duty cycle for a perceptual brightness `level`, out of `max_duty`.
+///
+/// Level 0 is off and 255 is `max_duty`; equal steps in `level` look like
+/// equal steps in brightness (see [`gamma_table`] for the reason).
+pub fn gamma(level: u8, max_duty: u16) -> u16 {
+    let full = u32::from(u16::MAX);
+    ((u32::from(GAMMA_LEVELS[level as usize]) * u32::from(max_duty) + full / 2) / full) as u16
+}
+
+/// Steps through a table forward, then backward, and so on.
+///
+/// The end values are returned once at each turn, so a brightness ramp
+/// played this way fades in and out without lingering at the extremes.
+pub struct PingPong {
+    index: usize,
+    rising: bool,
+}
+
+impl PingPong {
+    /// Start at the first entry of the table, moving forward.
+    pub const fn new() -> Self {
+        Self { index: 0, rising: true }
+    }
+
+    /// Return the current entry of `table` and move to the next one.
+    pub fn next<T: Copy>(&mut self, table: &[T]) -> T {
+        let value = table[self.index];
+        let last = table.len() - 1;
+        if self.rising {
+            if self.index < last {
+                self.index += 1;
+            } else {
+                self.rising = false;
+                self.index = self.index.saturating_sub(1);
+            }
+        } else if self.index > 0 {
+            self.index -= 1;
+        } else {
+            self.rising = true;
+            self.index = last.min(1);
+        }
+        value
+    }
+}
+
+impl Default for PingPong {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gamma_end_points() {
+        for max_duty in [0, 1, 255, 999, 4199, u16::MAX] {
+            assert_eq!(gamma(0, max_duty), 0);
+            assert_eq!(gamma(255, max_duty), max_duty);
+        }
+    }
+
+    #[test]
+    fn gamma_is_monotonic() {
+        assert!(GAMMA_LEVELS.windows(2).all(|pair| pair[0] <= pair[1]));
+        for max_duty in [255, 999, 4199, u16::MAX] {
+            for level in 0..255 {
+                assert!(gamma(level, max_duty) <= gamma(level + 1, max_duty));
+            }
+        }
+    }
+
+    #[test]
+    fn gamma_table_matches_float_curve() {
+        for level in [1, 64, 128, 200, 254] {
+            let expected = (level as f64 / 255.0).powf(2.5) * u16::MAX as f64;
+            assert!((GAMMA_LEVELS[level] as f64 - expected).abs() <= 1.0);
+        }
+        // The middle level needs less than a fifth of the full duty cycle
+        assert_eq!(gamma(128, 1000), 179);
+    }
+
+    #[test]
+    fn ping_pong_turns_at_both_ends() {
+        let table = [0, 1, 2, 3];
+        let mut stepper = PingPong::new();
+        let mut sequence = [0; 10];
+        for value in sequence.iter_mut() {
+            *value = stepper.next(&table);
+        }
+        assert_eq!(sequence, [0, 1, 2, 3, 2, 1, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn ping_pong_single_entry() {
+        let mut stepper = PingPong::new();
+        for _ in 0..3 {
+            assert_eq!(stepper.next(&[7]), 7);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! WS2812 ("NeoPixel") addressable LEDs driven by SPI.
+//!
+//! Each data bit sent to the LEDs is a high pulse followed by a low one, and
+//! only the pulse widths tell a 0 from a 1 (0.4/0.85 us for a 0, 0.8/0.45 us
+//! for a 1, ±150 ns). Sending three SPI bits per data bit on MOSI reproduces
+//! these pulses: `100` for a 0 and `110` for a 1.
+
+use embassy_stm32::mode::Async;
+use embassy_stm32::spi::{Error, Spi};
+use embassy_stm32::time::Hertz;
+use embassy_time::Timer;
+
+/// SPI clock giving 381 ns per SPI bit: 84 MHz APB2 divided by 32.
+///
+/// A `0` is then 381 ns high and 762 ns low, a `1` is 762 ns high and 381 ns
+/// low, all within the WS2812 tolerances. Any other kernel clock needs a
+/// frequency with a period close to 400 ns.
+pub const SPI_FREQUENCY: Hertz = Hertz(2_625_000);
+
+/// SPI bytes needed per LED: 24 data bits, three SPI bits each.
+pub const BYTES_PER_LED: usize = 9;
+
+/// Size of the buffer needed to drive `leds` LEDs.
+pub const fn buffer_size(leds: usize) -> usize {
+    leds * BYTES_PER_LED
+}
+
+/// 8-bit RGB color of one LED.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+/// Encode one byte, MSB first, into 24 SPI bits.
+fn encode_byte(byte: u8, out: &mut [u8]) {
+    let mut bits: u32 = 0;
+    for i in (0..8).rev() {
+        let symbol = if byte & (1 << i) != 0 { 0b110 } else { 0b100 };
+        bits = (bits << 3) | symbol;
+    }
+    out.copy_from_slice(&bits.to_be_bytes()[1..]);
+}
+
+/// Chain of WS2812 LEDs with the data input on an SPI MOSI pin.
+pub struct Ws2812<'d> {
+    spi: Spi<'d, Async>,
+    buffer: &'d mut [u8],
+}
+
+impl<'d> Ws2812<'d> {
+    /// Create a driver; `spi` must run at [`SPI_FREQUENCY`] in mode 0, MSB first.
+    ///
+    /// `buffer` holds the encoded bits and sets the maximum chain length:
+    /// use [`buffer_size`] to size it.
+    pub fn new(spi: Spi<'d, Async>, buffer: &'d mut [u8]) -> Self {
+        Self { spi, buffer }
+    }
+
+    /// Send `colors` to the chain, the first color going to the first LED.
+    ///
+    /// Colors beyond the buffer capacity are ignored.
+    pub async fn write(&mut self, colors: &[Rgb]) -> Result<(), Error> {
+        let leds = colors.len().min(self.buffer.len() / BYTES_PER_LED);
+        for (color, chunk) in colors[..leds].iter().zip(self.buffer.chunks_exact_mut(BYTES_PER_LED)) {
+            // The LEDs expect green first
+            encode_byte(color.g, &mut chunk[0..3]);
+            encode_byte(color.r, &mut chunk[3..6]);
+            encode_byte(color.b, &mut chunk[6..9]);
+        }
+        // DMA keeps the bits flowing without gaps, which would stretch pulses
+        self.spi.write(&self.buffer[..leds * BYTES_PER_LED]).await?;
+        // MOSI stays low after the last bit; holding it low for over 280 us
+        // latches the colors and resets the chain for the next frame
+        Timer::after_micros(300).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+/* Copyright (c) 2024 Perlatecnica APS ETS
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Hardware-in-the-loop tests, run on the NucleoF401RE with defmt-test.
+//!
+//! Required jumper wires:
+//! - PA5 (D13) to PA6 (D12): GPIO loopback
+//! - PA9 (D8) to PA10 (D2): USART1 TX to RX loopback
+//!
+//! Run with `cargo test --test on_target`; probe-rs flashes the board and
+//! reports each test through RTT.
+
+#![no_std]
+#![no_main]
+
+use embassy_stm32::gpio::{Input, Level, Output, Pull, Speed};
+use embassy_stm32::mode::Blocking;
+use embassy_stm32::usart::{self, Uart, UartRx, UartTx};
+use embassy_time::{block_for, Duration, Instant};
+use {defmt_rtt as _, panic_probe as _};
+
+struct Board {
+    out: Output<'static>,
+    input: Input<'static>,
+    tx: UartTx<'static, Blocking>,
+    rx: UartRx<'static, Blocking>,
+}
+
+/// Read one byte, or `None` if nothing arrives within `timeout`.
+///
+/// A missing jumper then fails the test instead of hanging it.
+fn read_byte(rx: &mut UartRx<'static, Blocking>, timeout: Duration) -> Option<u8> {
+    use embedded_hal::serial::Read;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match rx.read() {
+            Ok(byte) => return Some(byte),
+            Err(nb::Error::WouldBlock) if Instant::now() < deadline => {}
+            Err(nb::Error::WouldBlock) => return None,
+            Err(nb::Error::Other(e)) => defmt::panic!("UART error {}", e),
+        }
+    }
+}
+
+#[defmt_test::tests]
+mod tests {
+    use super::*;
+
+    #[init]
+    fn init() -> Board {
+        let p = embassy_stm32::init(Default::default());
+
+        let out = Output::new(p.PA5, Level::Low, Speed::Low);
+        // The pull-down makes a missing jumper read low
+        let input = Input::new(p.PA6, Pull::Down);
+
+        let uart = defmt::unwrap!(Uart::new_blocking(p.USART1, p.PA10, p.PA9, usart::Config::default()));
+        let (tx, rx) = uart.split();
+
+        Board { out, input, tx, rx }
+    }
+
+    #[test]
+    fn gpio_low_is_read_back(board: &mut Board) {
+        board.out.set_low();
+        block_for(Duration::from_micros(100));
+        defmt::assert!(board.input.is_low());
+    }
+
+    #[test]
+    fn gpio_high_is_read_back(board: &mut Board) {
+        board.out.set_high();
+        block_for(Duration::from_micros(100));
+        defmt::assert!(
+            board.input.is_high(),
+            "PA5 high not seen on PA6: is the jumper in place?"
+        );
+        board.out.set_low();
+    }
+
+    #[test]
+    fn uart_round_trips_bytes(board: &mut Board) {
+        for byte in [0x00, 0x55, 0xA5, 0xFF] {
+            defmt::unwrap!(board.tx.blocking_write(&[byte]));
+            defmt::unwrap!(board.tx.blocking_flush());
+            let received = read_byte(&mut board.rx, Duration::from_millis(10));
+            defmt::assert_eq!(received, Some(byte), "no loopback on PA9 -> PA10?");
+        }
+    }
+}